@@ -1,8 +1,16 @@
+use chrono::NaiveTime;
 use clap::{Parser, Subcommand, ValueEnum};
 
-use crate::commands::{
-    fits::FitsCmd, key_set::KeySetCmd, key_show::KeyShowCmd, list::ListCmd, path::PathCmd,
-    trim::TrimCmd, yt::YtCmd,
+use crate::{
+    commands::{
+        cache_clear::CacheClearCmd, cache_stats::CacheStatsCmd, fits::FitsCmd, key_set::KeySetCmd,
+        key_show::KeyShowCmd, list::ListCmd, path::PathCmd, plan::PlanCmd, speed::SpeedCmd,
+        trim::TrimCmd, yt::YtCmd,
+    },
+    core::{
+        format::{Rounding, TimeUnit},
+        time::{parse_clock_time, parse_timezone},
+    },
 };
 
 #[derive(Parser)]
@@ -12,6 +20,54 @@ pub struct Args {
     #[arg(short, long, global = true)]
     pub clip: bool,
 
+    /// Overrides the time at which the day ends (HH:MM), e.g. 01:00 or 22:00.
+    #[arg(long, value_name = "HH:MM", value_parser = parse_clock_time, global = true)]
+    pub day_ends: Option<NaiveTime>,
+
+    /// Timezone to calculate the day in (IANA name, e.g. Europe/Berlin). Defaults to the system timezone.
+    #[arg(long, value_name = "NAME", value_parser = parse_timezone, global = true)]
+    pub tz: Option<chrono_tz::Tz>,
+
+    /// Plans the session to start at the given time (HH:MM) instead of right now.
+    #[arg(long, value_name = "HH:MM", value_parser = parse_clock_time, global = true)]
+    pub start_at: Option<NaiveTime>,
+
+    /// Ignores the breaks set in the config file.
+    #[arg(long, global = true)]
+    pub no_breaks: bool,
+
+    /// Ignores timestamps in YouTube links (e.g. t=30m34s) and counts videos from the beginning.
+    #[arg(long, global = true)]
+    pub from_start: bool,
+
+    /// Neither reads nor writes the cache of YouTube API responses.
+    #[arg(long, global = true, conflicts_with = "refresh")]
+    pub no_cache: bool,
+
+    /// Answers from the cache of YouTube API responses only, however old, without making any requests.
+    #[arg(long, global = true, conflicts_with_all = ["no_cache", "refresh"])]
+    pub offline: bool,
+
+    /// Fetches everything again instead of using cached YouTube API responses, updating the cache.
+    #[arg(long, global = true)]
+    pub refresh: bool,
+
+    /// Shows durations in a single unit (e.g. 83.5 min) instead of days, hours, minutes and seconds.
+    #[arg(long, value_enum, global = true)]
+    pub unit: Option<TimeUnit>,
+
+    /// How to round displayed durations.
+    #[arg(long, value_enum, default_value_t = Rounding::Floor, global = true)]
+    pub round: Rounding,
+
+    /// Shows durations with millisecond precision (e.g. 1m2.5s).
+    #[arg(long, global = true)]
+    pub subseconds: bool,
+
+    /// Spells out durations (e.g. "1 hour, 23 minutes").
+    #[arg(long, global = true)]
+    pub long: bool,
+
     // Selects the color mode.
     #[arg(long, value_enum, default_value_t = ColorMode::Auto, global = true)]
     pub color: ColorMode,
@@ -35,6 +91,10 @@ pub enum Command {
     Yt(YtCmd),
     /// Check whether content(s) fit in the day or a given budget of time.
     Fits(FitsCmd),
+    /// Find the minimum multiplier needed to fit content in the day or a given budget of time.
+    Speed(SpeedCmd),
+    /// Plan a day-by-day schedule to finish content before a deadline.
+    Plan(PlanCmd),
     /// Lists all entries in a YouTube playlist.
     #[command(visible_alias = "ls")]
     List(ListCmd),
@@ -43,6 +103,11 @@ pub enum Command {
         #[command(subcommand)]
         command: KeySubcmd,
     },
+    /// Command group for managing the cache of YouTube API responses.
+    Cache {
+        #[command(subcommand)]
+        command: CacheSubcmd,
+    },
     /// Shows the path of the configuration file.
     Path(PathCmd),
 }
@@ -54,3 +119,11 @@ pub enum KeySubcmd {
     /// Sets the current API key.
     Set(KeySetCmd),
 }
+
+#[derive(Subcommand, Debug)]
+pub enum CacheSubcmd {
+    /// Shows what is cached and where.
+    Stats(CacheStatsCmd),
+    /// Deletes all cached responses.
+    Clear(CacheClearCmd),
+}
@@ -1,4 +1,128 @@
+use anyhow::bail;
+use chrono::NaiveTime;
+
+use crate::{
+    core::{
+        api::ApiClientManager,
+        cache::Cache,
+        config::Config,
+        format::TimeFormat,
+        sources::Source,
+        time::{BreakPolicy, DayContext, Zone},
+        youtils::{YoutubeId, get_youtube_api_key, get_youtube_id},
+    },
+    errors::{TCacheError, TConfigError, TYoutubeWarning},
+};
+
 pub struct Flags {
     /// Global flag: --clip
     pub clip: bool,
+    /// Global flag: --day-ends
+    pub day_ends: Option<NaiveTime>,
+    /// Global flag: --tz
+    pub tz: Option<chrono_tz::Tz>,
+    /// Global flag: --start-at
+    pub start_at: Option<NaiveTime>,
+    /// Global flag: --no-breaks
+    pub no_breaks: bool,
+    /// Global flag: --from-start
+    pub from_start: bool,
+    /// Global flag: --no-cache
+    pub no_cache: bool,
+    /// Global flag: --refresh
+    pub refresh: bool,
+    /// Global flag: --offline
+    pub offline: bool,
+    /// Global flags: --unit, --round, --subseconds and --long
+    pub format: TimeFormat,
+}
+
+impl Flags {
+    /// Resolves the day boundaries and timezone from the config file, with `--day-ends` and `--tz` taking
+    /// precedence, and applies `--start-at`.
+    pub fn day_context(&self, config: &Config) -> Result<DayContext, TConfigError> {
+        let mut bounds = config.day_bounds()?;
+
+        if let Some(end) = self.day_ends {
+            bounds.end = end;
+        }
+
+        let zone = match self.tz {
+            Some(tz) => Zone::Named(tz),
+            None => config.zone()?,
+        };
+
+        Ok(DayContext::new(bounds, zone).with_start_at(self.start_at))
+    }
+
+    /// Resolves the breaks from the config file, unless disabled using `--no-breaks`.
+    pub fn break_policy(&self, config: &Config) -> Result<Option<BreakPolicy>, TConfigError> {
+        if self.no_breaks {
+            Ok(None)
+        } else {
+            config.break_policy()
+        }
+    }
+
+    /// Extracts what a YouTube link points to, dropping its timestamp when `--from-start` is given.
+    #[must_use]
+    pub fn youtube_id(&self, link: &str) -> Option<YoutubeId> {
+        let id = get_youtube_id(link)?;
+
+        if self.from_start {
+            Some(id.without_start())
+        } else {
+            Some(id)
+        }
+    }
+
+    /// Parses a YouTube link or a duration, dropping the link's timestamp when `--from-start` is given.
+    #[must_use]
+    pub fn source(&self, input: &str) -> Option<Source> {
+        match Source::parse(input)? {
+            Source::Youtube(id) if self.from_start => Some(Source::Youtube(id.without_start())),
+            source => Some(source),
+        }
+    }
+
+    /// Opens the cache of API responses with the configured TTL, unless disabled using `--no-cache`. With
+    /// `--refresh`, cached responses are ignored but still replaced.
+    pub fn cache(&self, config: &Config) -> Result<Option<Cache>, TCacheError> {
+        if self.no_cache {
+            return Ok(None);
+        }
+
+        let mut cache = Cache::open(config.cache_ttl(), self.refresh)?;
+        if self.offline {
+            cache.allow_stale();
+        }
+        Ok(Some(cache))
+    }
+
+    /// Creates an API client for the configured base URL using the cache, which only answers from it with
+    /// `--offline`. If the cache can't be opened, requests go uncached with a warning, or fail with `--offline`.
+    pub fn manager<'a>(
+        &self,
+        key: &'a str,
+        config: &Config,
+    ) -> anyhow::Result<ApiClientManager<'a>> {
+        let manager = ApiClientManager::new(key).with_base(&config.api_base());
+
+        match self.cache(config) {
+            Ok(cache) => Ok(manager.with_cache(cache).with_offline(self.offline)),
+            Err(e) if self.offline => bail!("Can't answer offline without the cache: {e}"),
+            Err(e) => {
+                manager.warn(TYoutubeWarning::CacheUnavailable(e.to_string()));
+                Ok(manager)
+            }
+        }
+    }
+
+    /// Gets the YouTube API key, which isn't required with `--offline` since no requests are made.
+    pub fn api_key(&self) -> anyhow::Result<String> {
+        match get_youtube_api_key() {
+            Err(_) if self.offline => Ok(String::new()),
+            result => result,
+        }
+    }
 }
@@ -1,4 +1,5 @@
 pub mod args;
 pub mod flags;
+pub mod selection;
 
 pub use args::Args;
@@ -0,0 +1,112 @@
+use std::{num::NonZeroUsize, path::PathBuf};
+
+use anyhow::{Result, bail};
+use chrono::NaiveDate;
+use clap::Args;
+
+use crate::{
+    cli::flags::Flags,
+    core::{
+        api::ApiClientManager,
+        config::Config,
+        sources::{Source, Tally},
+        style::Style,
+        utils::print_warnings,
+        youtils::{ItemRange, PublishedRange, YoutubeId},
+    },
+};
+
+/// Options for where to read sources from and which videos of a playlist or channel to take, shared by `yt`,
+/// `fits` and `list`.
+#[derive(Debug, Default, Args)]
+pub struct Selection {
+    /// Reads more sources from a file, one per line. Blank lines and comments starting with # are skipped.
+    #[arg(long, value_name = "PATH")]
+    pub from_file: Option<PathBuf>,
+
+    /// Only uses the first link found in the clipboard or on stdin, rather than all of them.
+    #[arg(long)]
+    pub first: bool,
+
+    /// Max amount of items to traverse in a playlist (if one is passed). Defaults to the total length of the playlist.
+    #[arg(visible_alias = "max", long, default_value = "0")]
+    pub max_items: usize,
+
+    /// Starts at this position (counting from 1) in a playlist, overriding the index= of the link.
+    #[arg(long, value_name = "N", conflicts_with = "range")]
+    pub from: Option<NonZeroUsize>,
+
+    /// Only takes the playlist items in this range of positions, inclusive (e.g. 37..60, 37.. or ..60).
+    #[arg(long, value_name = "FIRST..LAST")]
+    pub range: Option<ItemRange>,
+
+    /// Only takes playlist/channel videos published on or after this date (YYYY-MM-DD).
+    #[arg(long, value_name = "DATE")]
+    pub since: Option<NaiveDate>,
+
+    /// Only takes playlist/channel videos published on or before this date (YYYY-MM-DD).
+    #[arg(long, value_name = "DATE")]
+    pub until: Option<NaiveDate>,
+}
+
+impl Selection {
+    /// Creates an API client taking only the videos published within `--since` and `--until`, using the cache
+    /// unless disabled and nothing else with `--offline`.
+    pub fn manager<'a>(
+        &self,
+        key: &'a str,
+        flags: &Flags,
+        config: &Config,
+    ) -> Result<ApiClientManager<'a>> {
+        Ok(flags.manager(key, config)?.with_published(PublishedRange {
+            since: self.since,
+            until: self.until,
+        }))
+    }
+
+    /// The positions to take from the given playlist or channel.
+    #[must_use]
+    pub fn items(&self, id: &YoutubeId) -> ItemRange {
+        ItemRange::resolve(
+            self.range,
+            self.from.map(NonZeroUsize::get),
+            id,
+            self.max_items,
+        )
+    }
+
+    /// Adds up YouTube links and durations, counting videos shared between them only once. The API key is
+    /// only required if there is at least one link.
+    pub fn tally(&self, inputs: &[String], flags: &Flags, style: &Style) -> Result<Tally> {
+        let mut sources = Vec::new();
+        for input in inputs {
+            let Some(source) = flags.source(input) else {
+                bail!(
+                    "Not a valid YouTube URL or duration: {input}\nPass a link to a video, playlist or channel, a bare video/playlist ID, or a duration (e.g. 45m)."
+                )
+            };
+            sources.push((input, source));
+        }
+
+        let key = if sources.iter().any(|(_, s)| matches!(s, Source::Youtube(_))) {
+            flags.api_key()?
+        } else {
+            String::new()
+        };
+        let config = Config::load_or_default().map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        let manager = self.manager(&key, flags, &config)?;
+
+        let mut tally = Tally::default();
+        for (input, source) in sources {
+            match source {
+                Source::Duration(duration) => tally.add_duration(input, duration),
+                Source::Youtube(id) => tally
+                    .add_youtube(input, &manager, &id, self.items(&id))
+                    .map_err(|e| anyhow::anyhow!("Failed to fetch details for {input}: {e}"))?,
+            }
+        }
+
+        print_warnings(&manager, style);
+        Ok(tally)
+    }
+}
@@ -0,0 +1,23 @@
+use crate::{
+    cli::flags::Flags,
+    commands::Runnable,
+    core::{cache::Cache, style::Style},
+};
+use anyhow::Result;
+use clap::Args;
+
+#[derive(Debug, Args)]
+pub struct CacheClearCmd;
+
+impl Runnable for CacheClearCmd {
+    fn run(self, _: &Flags, _: &Style) -> Result<()> {
+        let path = Cache::file().map_err(|e| anyhow::anyhow!(e.to_string()))?;
+
+        if Cache::clear().map_err(|e| anyhow::anyhow!(e.to_string()))? {
+            println!("Cleared the cache at {}.", path.display());
+        } else {
+            println!("The cache is already empty.");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{
+    cli::flags::Flags,
+    commands::Runnable,
+    core::{cache::Cache, config::Config, style::Style},
+};
+use anyhow::Result;
+use clap::Args;
+
+#[derive(Debug, Args)]
+pub struct CacheStatsCmd;
+
+impl Runnable for CacheStatsCmd {
+    fn run(self, flags: &Flags, style: &Style) -> Result<()> {
+        let config = Config::load_or_default().map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        let ttl = config.cache_ttl();
+        let cache = Cache::open(ttl, false).map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        let stats = cache.stats();
+        let size = std::fs::metadata(cache.path()).map_or(0, |m| m.len());
+
+        let mut message = vec![
+            format!(
+                "{}{}{}",
+                style.bold(),
+                cache.path().display(),
+                style.reset()
+            ),
+            format!("Size: {:.1} KiB", size as f64 / 1024.0),
+            format!("Videos: {}", stats.videos),
+            format!("Playlists: {}", stats.playlists),
+            format!("Channels: {}", stats.channels),
+            format!(
+                "Expired: {} (entries older than {})",
+                stats.expired,
+                flags.format.format(ttl)
+            ),
+        ];
+
+        if let Some(oldest) = stats.oldest {
+            message.push(format!("Oldest entry: {} ago", flags.format.format(oldest)));
+        }
+
+        println!("{}", message.join("\n"));
+        Ok(())
+    }
+}
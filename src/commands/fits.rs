@@ -1,80 +1,85 @@
 use crate::{
-    cli::flags::Flags,
+    cli::{flags::Flags, selection::Selection},
     commands::Runnable,
     core::{
-        api::ApiClientManager,
+        config::Config,
         style::Style,
-        time::{parse_duration, parse_time, time_in_day_after},
-        utils::choose_or_grab_link,
-        youtils::{get_youtube_api_key, get_youtube_id},
+        time::{parse_duration, time_in_day_after},
+        utils::{choose_or_grab_links, print_subtotals},
     },
 };
-use anyhow::{Result, bail};
+use anyhow::Result;
 use clap::Args;
 
 #[derive(Debug, Default, Args)]
 pub struct FitsCmd {
-    /// The URL, or link, for the YouTube video.
-    #[arg(required_unless_present = "clip")]
-    link: Option<String>,
+    /// The URLs, or links, for YouTube videos, playlists or channels. Plain durations (e.g. 45m) can be mixed in, and - reads more from stdin.
+    #[arg(required_unless_present_any = ["clip", "from_file"])]
+    sources: Vec<String>,
 
-    /// The budget duration string. By default uses the remaining time for the day.
+    /// The budget duration string (e.g. 2h4m, "1.5 hrs", 3h-20m). By default uses the remaining time for the day.
     #[arg(short, long)]
     budget: Option<String>,
 
-    /// Max amount of items to traverse in a playlist.
-    #[arg(long, default_value = "0")]
-    max_items: usize,
+    #[command(flatten)]
+    selection: Selection,
 }
 
 impl Runnable for FitsCmd {
     fn run(self, flags: &Flags, style: &Style) -> Result<()> {
-        let key = get_youtube_api_key()?;
-        let link = choose_or_grab_link(self.link, flags.clip)?;
-        let manager = ApiClientManager::new(&key);
-        let id = get_youtube_id(&link);
+        let config = Config::load_or_default().map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        let day = flags
+            .day_context(&config)
+            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        let breaks = flags
+            .break_policy(&config)
+            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        let sources = choose_or_grab_links(
+            self.sources,
+            self.selection.from_file.as_deref(),
+            flags.clip,
+            self.selection.first,
+        )?;
+        let tally = self.selection.tally(&sources, flags, style)?;
 
-        let Some(id) = id else {
-            bail!(
-                "Not a valid YouTube URL! Only videos/embeds/shorts URLs are supported in the `yt` command."
-            )
-        };
+        print_subtotals(&tally, &flags.format);
 
-        let (vid_total_duration, item_count) = manager
-            .fetch_duration_from_id(&id, self.max_items)
-            .map_err(|e| anyhow::anyhow!("Failed to fetch details from URL: {e}"))?;
+        let vid_total_duration = tally.total();
+        let session = breaks
+            .map(|b| b.session_length(vid_total_duration))
+            .unwrap_or(vid_total_duration);
 
         let message = {
             let status = if let Some(b) = &self.budget {
                 let (limit_duration, _) = parse_duration(b)
                     .map_err(|e| anyhow::anyhow!("Failed to parse budget duration: {e}"))?;
 
-                if limit_duration > vid_total_duration {
+                if limit_duration > session {
                     format!(
                         "{}Fits in budget!{}\n\nExtra time left: {}",
                         style.boldgreen(),
                         style.reset(),
-                        parse_time(limit_duration - vid_total_duration)
+                        flags.format.format(limit_duration.abs_diff(session))
                     )
-                } else if limit_duration < vid_total_duration {
+                } else if limit_duration < session {
                     format!(
                         "{}Time overrun by {}!{}",
                         style.boldred(),
-                        parse_time(vid_total_duration - limit_duration),
+                        flags.format.format(session.abs_diff(limit_duration)),
                         style.reset()
                     )
                 } else {
                     "Duration match! Would finish on time.".to_string()
                 }
             } else {
-                let time_left = time_in_day_after(vid_total_duration);
+                let time_left = time_in_day_after(session, &day);
 
-                if time_left != 0.0 {
+                if !time_left.is_zero() {
                     format!(
                         "{}Fits in day!{}\n\nTime left afterwards: {}",
                         style.boldgreen(),
                         style.reset(),
-                        parse_time(time_left)
+                        flags.format.format(time_left)
                     )
                 } else {
                     format!(
@@ -85,7 +90,32 @@ impl Runnable for FitsCmd {
                 }
             };
 
-            format!("\n{status}\n(counted {item_count} videos)\n")
+            let finish = day
+                .finish_time(session)
+                .map(|f| format!("Done at: {f}\n"))
+                .unwrap_or_default();
+            let breaks = if session != vid_total_duration {
+                format!(
+                    "Session with breaks: {} (watch time {})\n",
+                    flags.format.format(session),
+                    flags.format.format(vid_total_duration)
+                )
+            } else {
+                String::new()
+            };
+
+            let mut counted = vec![format!("counted {} item(s)", tally.items())];
+            if let [subtotal] = tally.subtotals.as_slice()
+                && let Some(start) = subtotal.start
+            {
+                counted.push(format!("from {} in", flags.format.format(start)));
+            }
+            if tally.duplicates() > 0 {
+                counted.push(format!("skipped {} duplicate(s)", tally.duplicates()));
+            }
+            let counted = counted.join(", ");
+
+            format!("\n{status}\n{breaks}{finish}({counted})\n")
         };
 
         println!("{message}");
@@ -1,7 +1,12 @@
 use crate::{
     cli::flags::Flags,
     commands::Runnable,
-    core::{api::ApiClientManager, config::Config, style::Style, youtils::YoutubeId},
+    core::{
+        api::ApiClientManager,
+        config::Config,
+        style::Style,
+        youtils::{ItemRange, YoutubeId},
+    },
 };
 use anyhow::{Result, bail};
 use clap::Args;
@@ -22,13 +27,16 @@ impl Runnable for KeySetCmd {
 
         if !self.no_check {
             println!("Testing key... (use --no-check to skip)");
-            let client = ApiClientManager::new(&self.api_key);
+            let client = ApiClientManager::new(&self.api_key).with_base(&config.api_base());
 
-            let id = YoutubeId {
+            let id = YoutubeId::Video {
                 id: "dQw4w9WgXcQ".to_string(),
-                is_playlist: false,
+                start: None,
             };
-            if let Err(_) = client.fetch_duration_from_id(&id, 1) {
+            if client
+                .fetch_duration_from_id(&id, ItemRange::default())
+                .is_err()
+            {
                 bail!("{}Invalid API key passed!{}", style.red(), style.reset())
             }
         }
@@ -1,11 +1,13 @@
+use std::collections::HashSet;
+
 use crate::{
-    cli::flags::Flags,
+    cli::{flags::Flags, selection::Selection},
     commands::Runnable,
     core::{
-        api::ApiClientManager,
+        config::Config,
         style::Style,
-        utils::choose_or_grab_link,
-        youtils::{get_youtube_api_key, get_youtube_id},
+        utils::{choose_or_grab_links, print_warnings},
+        youtils::get_youtube_id,
     },
 };
 use anyhow::{Result, bail};
@@ -13,42 +15,58 @@ use clap::Args;
 
 #[derive(Debug, Default, Args)]
 pub struct ListCmd {
-    /// The link to the YouTube playlist.
-    #[arg(required_unless_present = "clip")]
-    link: Option<String>,
+    /// The links to YouTube playlists or channels. Pass - to read them from stdin.
+    #[arg(required_unless_present_any = ["clip", "from_file"])]
+    links: Vec<String>,
 
-    /// The maximum amount of items to list from the given playlist.
-    #[arg(visible_alias = "max", long, default_value = "0")]
-    max_items: usize,
+    #[command(flatten)]
+    selection: Selection,
 }
 
 impl Runnable for ListCmd {
-    fn run(self, flags: &Flags, _: &Style) -> Result<()> {
-        let link = choose_or_grab_link(self.link, flags.clip)?;
-        let key = get_youtube_api_key()?;
-
-        let manager = ApiClientManager::new(&key);
-        let id = match get_youtube_id(&link) {
-            Some(id) => {
-                if !id.is_playlist {
-                    bail!("Not a valid YouTube playlist ID!")
+    fn run(self, flags: &Flags, style: &Style) -> Result<()> {
+        let links = choose_or_grab_links(
+            self.links,
+            self.selection.from_file.as_deref(),
+            flags.clip,
+            self.selection.first,
+        )?;
+        let key = flags.api_key()?;
+
+        let config = Config::load_or_default().map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        let manager = self.selection.manager(&key, flags, &config)?;
+        let mut seen = HashSet::new();
+
+        for link in &links {
+            let id = match get_youtube_id(link) {
+                Some(id) => {
+                    if !id.is_collection() {
+                        bail!("Not a valid YouTube playlist or channel: {link}")
+                    }
+                    id
                 }
-                id
+                None => bail!("No YouTube playlist or channel was found in this link: {link}"),
+            };
+
+            let ids: Vec<String> = manager
+                .expand_id(&id, self.selection.items(&id))
+                .map_err(|e| anyhow::anyhow!("Failed to get playlist item IDs: {e}"))?
+                .into_iter()
+                .filter(|id| seen.insert(id.clone()))
+                .collect();
+            let videos = manager
+                .fetch_video_items(&ids)
+                .map_err(|e| anyhow::anyhow!("Failed to fetch playlist videos: {e}"))?;
+
+            if links.len() > 1 {
+                println!("{}{link}{}", style.bold(), style.reset());
+            }
+            for v in videos {
+                println!("{}", v.snippet.title)
             }
-            None => bail!("No YouTube playlist ID was found in this link."),
-        };
-
-        let ids = manager
-            .expand_id(&id, self.max_items)
-            .map_err(|e| anyhow::anyhow!("Failed to get playlist item IDs: {e}"))?;
-        let videos = manager
-            .fetch_video_items(&ids)
-            .map_err(|e| anyhow::anyhow!("Failed to fetch playlist videos: {e}"))?;
-
-        for v in videos {
-            println!("{}", v.snippet.title)
         }
 
+        print_warnings(&manager, style);
         Ok(())
     }
 }
@@ -0,0 +1,220 @@
+use crate::{
+    cli::flags::Flags,
+    commands::Runnable,
+    core::{
+        config::Config,
+        duration::Duration,
+        plan::{PlanItem, WeeklyBudget, schedule},
+        sources::Source,
+        style::Style,
+        time::{TimeConfig, parse_duration},
+        utils::{choose_or_grab_link, print_warnings},
+        youtils::ItemRange,
+    },
+};
+use anyhow::{Result, bail};
+use chrono::NaiveDate;
+use clap::Args;
+
+#[derive(Debug, Args)]
+pub struct PlanCmd {
+    /// A duration (e.g. 200*45m) or the URL of a YouTube video/playlist.
+    #[arg(required_unless_present = "clip")]
+    source: Option<String>,
+
+    /// The speed multiplier (e.g. 1.25x, 1.25).
+    #[arg(short, long)]
+    multiplier: String,
+
+    /// The last day (inclusive) to finish everything by, as YYYY-MM-DD.
+    #[arg(long)]
+    deadline: NaiveDate,
+
+    /// The time available each day (e.g. 3h).
+    #[arg(short, long)]
+    daily: Option<String>,
+
+    /// The time available on a given weekday, overriding --daily (e.g. sat=5h). Can be repeated.
+    #[arg(short, long = "weekday", value_name = "DAY=DURATION")]
+    weekdays: Vec<String>,
+
+    /// Max amount of items to traverse in a playlist.
+    #[arg(visible_alias = "max", long, default_value = "0")]
+    max_items: usize,
+}
+
+impl Runnable for PlanCmd {
+    fn run(self, flags: &Flags, style: &Style) -> Result<()> {
+        if self.daily.is_none() && self.weekdays.is_empty() {
+            bail!("A daily budget (--daily) or per-weekday budgets (--weekday) are required.")
+        }
+
+        let config = Config::load_or_default().map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        let day = flags
+            .day_context(&config)
+            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        let range = config.multiplier_range();
+
+        // everything local is checked before spending API quota on a link
+        let today = day.local(day.now).date();
+        if self.deadline < today {
+            bail!("The deadline ({}) is already in the past.", self.deadline)
+        }
+        let multiplier = TimeConfig::with_range("0s", &self.multiplier, &range)
+            .map_err(|e| anyhow::anyhow!("Time configuration error: {e}"))?
+            .multiplier;
+        let breaks = flags
+            .break_policy(&config)
+            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+
+        let mut budget = WeeklyBudget::default();
+        if let Some(daily) = &self.daily {
+            budget.default = parse_duration(daily)
+                .map_err(|e| anyhow::anyhow!("Failed to parse daily budget: {e}"))?
+                .0;
+        }
+        for spec in &self.weekdays {
+            budget
+                .add_override(spec)
+                .map_err(|e| anyhow::anyhow!("Failed to parse weekday budget: {e}"))?;
+        }
+
+        let source = choose_or_grab_link(self.source, flags.clip)?;
+
+        // durations with per-segment speeds aren't sources, but are planned below all the same
+        let items: Vec<PlanItem> = if let Some(Source::Youtube(id)) = flags.source(&source) {
+            let key = flags.api_key()?;
+            let manager = flags.manager(&key, &config)?;
+            let items = ItemRange::resolve(None, None, &id, self.max_items);
+            let ids = manager
+                .expand_id(&id, items)
+                .map_err(|e| anyhow::anyhow!("Failed to get playlist item IDs: {e}"))?;
+            let videos = manager
+                .fetch_video_items(&ids)
+                .map_err(|e| anyhow::anyhow!("Failed to fetch playlist videos: {e}"))?;
+            print_warnings(&manager, style);
+
+            // only single videos have a timestamp, so this applies to the one item at most
+            let start = id.start();
+
+            videos
+                .into_iter()
+                .map(|v| {
+                    let duration = v
+                        .content_details
+                        .duration
+                        .parse::<Duration>()
+                        .map_err(|e| {
+                            anyhow::anyhow!(
+                                "Failed to parse the duration of {}: {e}",
+                                v.snippet.title
+                            )
+                        })?
+                        .saturating_sub(start.unwrap_or(Duration::ZERO));
+
+                    Ok(PlanItem {
+                        title: match start {
+                            Some(start) => format!(
+                                "{} (from {} in)",
+                                v.snippet.title,
+                                flags.format.format(start)
+                            ),
+                            None => v.snippet.title,
+                        },
+                        duration: duration.checked_div_f64(multiplier).unwrap_or(duration),
+                    })
+                })
+                .collect::<Result<_>>()?
+        } else {
+            let cfg = TimeConfig::with_range(&source, &self.multiplier, &range)
+                .map_err(|e| anyhow::anyhow!("Time configuration error: {e}"))?;
+            let trimmed = cfg.trim().map_err(|e| anyhow::anyhow!("Trim error: {e}"))?;
+
+            // `12*45m` stands for twelve separate items
+            trimmed
+                .segments
+                .iter()
+                .enumerate()
+                .flat_map(|(i, s)| {
+                    let each = s
+                        .duration
+                        .checked_div_f64(s.count as f64)
+                        .unwrap_or(s.duration);
+
+                    (1..=s.count).map(move |n| PlanItem {
+                        title: if s.count > 1 {
+                            format!("Segment {} ({n}/{})", i + 1, s.count)
+                        } else {
+                            format!("Segment {}", i + 1)
+                        },
+                        duration: each,
+                    })
+                })
+                .collect()
+        };
+
+        let plan = schedule(
+            items,
+            &budget,
+            today,
+            self.deadline,
+            day.time_left(),
+            breaks,
+        );
+
+        let mut message = Vec::new();
+        let mut counter = 0;
+
+        for d in &plan.days {
+            let breaks = d.session.saturating_sub(d.total());
+            message.push(format!(
+                "\n{}{} {}{} ({} of {}{})",
+                style.bold(),
+                d.date.format("%a"),
+                d.date,
+                style.reset(),
+                flags.format.format(d.session),
+                flags.format.format(d.budget),
+                if breaks.is_zero() {
+                    String::new()
+                } else {
+                    format!(", incl. {} of breaks", flags.format.format(breaks))
+                }
+            ));
+
+            for item in &d.items {
+                counter += 1;
+                message.push(format!(
+                    "  {counter}. {} [{}]",
+                    item.title,
+                    flags.format.format(item.duration)
+                ));
+            }
+        }
+
+        if plan.unscheduled.is_empty() {
+            if let Some(last) = plan.days.last() {
+                message.push(format!(
+                    "\n{}All done by {} {}!{}",
+                    style.boldgreen(),
+                    last.date.format("%a"),
+                    last.date,
+                    style.reset()
+                ));
+            }
+        } else {
+            message.push(format!(
+                "\n{}{} item(s) ({}) won't fit before the deadline!{}",
+                style.boldred(),
+                plan.unscheduled.len(),
+                flags
+                    .format
+                    .format(plan.unscheduled.iter().map(|i| i.duration).sum()),
+                style.reset()
+            ));
+        }
+
+        println!("{}\n", message.join("\n"));
+        Ok(())
+    }
+}
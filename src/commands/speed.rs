@@ -0,0 +1,150 @@
+use crate::{
+    cli::flags::Flags,
+    commands::Runnable,
+    core::{
+        config::Config,
+        sources::Source,
+        style::Style,
+        time::{parse_duration, required_multiplier, suggested_multiplier},
+        utils::{choose_or_grab_link, print_warnings},
+        youtils::ItemRange,
+    },
+};
+use anyhow::{Result, bail};
+use clap::Args;
+
+#[derive(Debug, Default, Args)]
+pub struct SpeedCmd {
+    /// A duration (e.g. 4h, 12*45m) or the URL of a YouTube video/playlist.
+    #[arg(required_unless_present = "clip")]
+    source: Option<String>,
+
+    /// The budget duration string. By default uses the remaining time for the day.
+    #[arg(short, long)]
+    budget: Option<String>,
+
+    /// The step to round the required multiplier up to (e.g. 0.05, 0.25).
+    #[arg(short, long, default_value = "0.05")]
+    step: f64,
+
+    /// Max amount of items to traverse in a playlist.
+    #[arg(visible_alias = "max", long, default_value = "0")]
+    max_items: usize,
+}
+
+impl Runnable for SpeedCmd {
+    fn run(self, flags: &Flags, style: &Style) -> Result<()> {
+        if !(self.step.is_finite() && self.step > 0.0) {
+            bail!("The step must be a positive number.")
+        }
+
+        let config = Config::load_or_default().map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        let day = flags
+            .day_context(&config)
+            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        let source = choose_or_grab_link(self.source, flags.clip)?;
+
+        let ((duration, item_count), start) = match flags.source(&source) {
+            Some(Source::Youtube(id)) => {
+                let key = flags.api_key()?;
+                let manager = flags.manager(&key, &config)?;
+
+                let items = ItemRange::resolve(None, None, &id, self.max_items);
+                let fetched = manager
+                    .fetch_duration_from_id(&id, items)
+                    .map_err(|e| anyhow::anyhow!("Failed to fetch details from URL: {e}"))?;
+                print_warnings(&manager, style);
+
+                (fetched, id.start())
+            }
+            Some(Source::Duration((duration, splits))) => ((duration, splits as usize), None),
+            None => {
+                let error = parse_duration(&source).err();
+                bail!(
+                    "Failed to parse duration: {}",
+                    error.map_or_else(|| source.clone(), |e| e.to_string())
+                )
+            }
+        };
+
+        let budget = match &self.budget {
+            Some(b) => {
+                parse_duration(b)
+                    .map_err(|e| anyhow::anyhow!("Failed to parse budget duration: {e}"))?
+                    .0
+            }
+            None => day.time_left(),
+        };
+
+        let breaks = flags
+            .break_policy(&config)
+            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+
+        let Some(required) = required_multiplier(duration, budget, breaks) else {
+            bail!(
+                "{}There is no time left in the budget.{}",
+                style.boldred(),
+                style.reset()
+            )
+        };
+
+        let range = config.multiplier_range();
+        let multiplier = suggested_multiplier(required, self.step).max(range.min);
+        let new_duration = duration.checked_div_f64(multiplier).unwrap_or(duration);
+        let session = breaks.map_or(new_duration, |b| b.session_length(new_duration));
+        let fmt = |d| flags.format.format(d);
+
+        let mut message = vec![if required <= 1.0 {
+            format!(
+                "\n{}Required speed: {multiplier}x{} (no speed-up needed, it already fits)",
+                style.bold(),
+                style.reset()
+            )
+        } else {
+            format!(
+                "\n{}Required speed: {multiplier}x{} (exactly {required:.3}x)",
+                style.bold(),
+                style.reset()
+            )
+        }];
+
+        if session == new_duration {
+            message.push(format!(
+                "Finishes in: {} of {} budgeted",
+                fmt(new_duration),
+                fmt(budget)
+            ));
+        } else {
+            message.extend([
+                format!("Finishes in: {}", fmt(new_duration)),
+                format!(
+                    "Session with breaks: {} of {} budgeted (+{} of breaks)",
+                    fmt(session),
+                    fmt(budget),
+                    fmt(session.saturating_sub(new_duration))
+                ),
+            ]);
+        }
+
+        if let Some(finish) = day.finish_time(session) {
+            message.push(format!("Done at: {finish}"));
+        }
+
+        if multiplier > range.max {
+            message.push(format!(
+                "{}Warning: exceeds the maximum multiplier of {}x!{}",
+                style.boldred(),
+                range.max,
+                style.reset()
+            ));
+        }
+
+        let counted = match start {
+            Some(start) => format!("counted {item_count} item(s), from {} in", fmt(start)),
+            None => format!("counted {item_count} item(s)"),
+        };
+
+        println!("{}\n({counted})\n", message.join("\n"));
+        Ok(())
+    }
+}
@@ -1,64 +1,334 @@
 use crate::{
     cli::flags::Flags,
     commands::Runnable,
-    core::{style::Style, time::TimeConfig},
+    core::{
+        config::Config,
+        format::TimeFormat,
+        style::Style,
+        time::{BreakPolicy, DayContext, MultiplierRange, TimeConfig, TrimResult},
+    },
 };
 use anyhow::Result;
 use clap::Args;
 
 #[derive(Debug, Default, Args)]
 pub struct TrimCmd {
-    /// Duration of the content (e.g. 1h2m1s, 1h1s, 2d49s).
+    /// Duration of the content (e.g. 1h2m1s, "1 hour 2 minutes", 1:02:01, 12*45m, 3h-20m, 1h@1.25x+30m@2x).
     pub duration: String,
 
-    /// The speed multiplier (e.g. 1.25x, 1.25).
+    /// The speed multiplier (e.g. 1.25x, 1.25, 0.75x), used for segments without one of their own.
+    /// Pass several comma-separated multipliers (e.g. 1.25,1.5,2) to compare them in a table.
     #[arg(short, long)]
     pub multiplier: String,
 }
 
 impl Runnable for TrimCmd {
-    fn run(self, _: &Flags, style: &Style) -> Result<()> {
-        let cfg = TimeConfig::new(&self.duration, &self.multiplier)
-            .map_err(|e| anyhow::anyhow!("Time configuration error: {e}"))?;
+    fn run(self, flags: &Flags, style: &Style) -> Result<()> {
+        let config = Config::load_or_default().map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        let day = flags
+            .day_context(&config)
+            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        let breaks = flags
+            .break_policy(&config)
+            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
 
-        let (new_duration, time_saved, splits) =
-            cfg.trim().map_err(|e| anyhow::anyhow!("Trim error: {e}"))?;
+        if self.multiplier.contains(',') {
+            return compare(
+                &self.duration,
+                &self.multiplier,
+                &config,
+                &day,
+                breaks,
+                &flags.format,
+                style,
+            );
+        }
+
+        let has_breaks = breaks.is_some();
+        let cfg =
+            TimeConfig::with_range(&self.duration, &self.multiplier, &config.multiplier_range())
+                .map_err(|e| anyhow::anyhow!("Time configuration error: {e}"))?
+                .with_breaks(breaks);
+
+        let TrimResult {
+            duration: new_duration,
+            saved: time_saved,
+            added: time_added,
+            splits,
+            session,
+            segments,
+        } = cfg.trim().map_err(|e| anyhow::anyhow!("Trim error: {e}"))?;
+
+        let annotated = segments.iter().any(|s| s.annotated);
 
-        if time_saved <= 0.0 {
+        // with breaks or per-segment speeds, there's more to show than the (unchanged) duration
+        if time_saved.is_zero() && time_added.is_zero() && !annotated && !has_breaks {
             println!("No time saved. Would finish in linear time.");
             return Ok(());
         }
 
-        let parsed = crate::core::time::parse_time(new_duration);
-        let remaining = crate::core::time::time_in_day_after(new_duration);
-        let saved = crate::core::time::parse_time(time_saved);
-
-        let message = [
-            format!(
-                "\nFinishes in: {} ",
-                if splits > 1 {
-                    format!("{parsed} (all {splits} durations)")
-                } else {
-                    parsed
-                }
-            ),
-            if remaining != 0.0 {
+        let fmt = |d| flags.format.format(d);
+        let parsed = fmt(new_duration);
+        let remaining = crate::core::time::time_in_day_after(session, &day);
+
+        let mut message = vec![format!(
+            "\nFinishes in: {} ",
+            if splits > 1 {
+                format!("{parsed} (all {splits} durations)")
+            } else {
+                parsed
+            }
+        )];
+
+        if session != new_duration {
+            message.push(format!(
+                "Session with breaks: {} (+{} of breaks)",
+                fmt(session),
+                fmt(session.saturating_sub(new_duration))
+            ));
+        }
+
+        if let Some(finish) = day.finish_time(session) {
+            message.push(format!("Done at: {finish}"));
+        }
+
+        if annotated {
+            message.push("Segments:".to_string());
+            message.extend(segments.iter().enumerate().map(|(i, s)| {
                 format!(
-                    "Time in day left: {} ",
-                    if remaining == 0.0 {
-                        "0s".to_string()
+                    "  {}. {}{} @ {}x -> {} ({})",
+                    i + 1,
+                    fmt(s.original),
+                    if s.count > 1 {
+                        format!(" ({} durations)", s.count)
                     } else {
-                        crate::core::time::parse_time(remaining)
-                    }
+                        String::new()
+                    },
+                    s.multiplier,
+                    fmt(s.duration),
+                    if s.added.is_zero() {
+                        format!("saved {}", fmt(s.saved))
+                    } else {
+                        format!("added {}", fmt(s.added))
+                    },
                 )
+            }));
+        }
+
+        message.extend([
+            if !remaining.is_zero() {
+                format!("Time in day left: {} ", fmt(remaining))
             } else {
                 "Cannot finish today.".to_string()
             },
-            format!("{}Saved {saved}!{}\n", style.boldgreen(), style.reset()),
-        ]
-        .join("\n");
+            if time_saved.is_zero() && time_added.is_zero() {
+                "No time saved overall.\n".to_string()
+            } else if time_added.is_zero() {
+                format!(
+                    "{}Saved {}!{}\n",
+                    style.boldgreen(),
+                    fmt(time_saved),
+                    style.reset()
+                )
+            } else {
+                format!(
+                    "{}Added {}.{}\n",
+                    style.boldred(),
+                    fmt(time_added),
+                    style.reset()
+                )
+            },
+        ]);
 
-        println!("{message}");
+        println!("{}", message.join("\n"));
         Ok(())
     }
 }
+
+/// Prints a table comparing the results of several comma-separated multipliers.
+fn compare(
+    duration: &str,
+    multipliers: &str,
+    config: &Config,
+    day: &DayContext,
+    breaks: Option<BreakPolicy>,
+    format: &TimeFormat,
+    style: &Style,
+) -> Result<()> {
+    let rows = comparison_rows(
+        duration,
+        multipliers,
+        &config.multiplier_range(),
+        day,
+        breaks,
+        format,
+    )?;
+
+    let widths: Vec<usize> = (0..rows[0].len())
+        .map(|i| rows.iter().map(|r| r[i].len()).max().unwrap_or(0))
+        .collect();
+
+    println!();
+    for (i, row) in rows.iter().enumerate() {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+
+        if i == 0 {
+            println!("{}{}{}", style.bold(), line.trim_end(), style.reset());
+        } else {
+            let color = if row.last().is_some_and(|fits| fits == "yes") {
+                style.green()
+            } else {
+                style.red()
+            };
+            println!("{color}{}{}", line.trim_end(), style.reset());
+        }
+    }
+    println!();
+
+    Ok(())
+}
+
+/// Computes the cells of the comparison table, starting with its header. The session column is only
+/// included when there are breaks.
+fn comparison_rows(
+    duration: &str,
+    multipliers: &str,
+    range: &MultiplierRange,
+    day: &DayContext,
+    breaks: Option<BreakPolicy>,
+    format: &TimeFormat,
+) -> Result<Vec<Vec<String>>> {
+    let fmt = |d| format.format(d);
+    let time_left = day.time_left();
+
+    // like a single trim, "Finishes in" is the watch time, with the session time in a column of its own
+    let mut header = vec![
+        "Speed",
+        "Finishes in",
+        "Session",
+        "Saved",
+        "Done at",
+        "Fits today",
+    ];
+    if breaks.is_none() {
+        header.remove(2);
+    }
+    let mut rows = vec![header.into_iter().map(str::to_string).collect::<Vec<_>>()];
+
+    for multiplier in multipliers.split(',').map(str::trim) {
+        let result = TimeConfig::with_range(duration, multiplier, range)
+            .map_err(|e| anyhow::anyhow!("Time configuration error ({multiplier}): {e}"))?
+            .with_breaks(breaks)
+            .trim()
+            .map_err(|e| anyhow::anyhow!("Trim error: {e}"))?;
+
+        let mut row = vec![
+            format!("{}x", multiplier.trim_end_matches('x')),
+            fmt(result.duration),
+            fmt(result.session),
+            if result.added.is_zero() {
+                fmt(result.saved)
+            } else {
+                format!("-{}", fmt(result.added))
+            },
+            day.finish_time(result.session)
+                .map(|f| f.to_string())
+                .unwrap_or_default(),
+            if result.session <= time_left {
+                "yes".to_string()
+            } else {
+                "no".to_string()
+            },
+        ];
+        if breaks.is_none() {
+            row.remove(2);
+        }
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+    use crate::core::time::{DayBounds, Zone};
+
+    fn day() -> DayContext {
+        let now = "2026-03-01T20:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        DayContext::new(DayBounds::default(), Zone::Named(chrono_tz::UTC)).at(now)
+    }
+
+    fn rows(duration: &str, multipliers: &str, breaks: Option<BreakPolicy>) -> Vec<Vec<String>> {
+        comparison_rows(
+            duration,
+            multipliers,
+            &MultiplierRange::default(),
+            &day(),
+            breaks,
+            &TimeFormat::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_comparison_rows() {
+        assert_eq!(
+            rows("3h", "1.5, 2x,0.75x", None),
+            vec![
+                vec!["Speed", "Finishes in", "Saved", "Done at", "Fits today"],
+                vec!["1.5x", "2h", "1h", "22:00", "yes"],
+                vec!["2x", "1h30m", "1h30m", "21:30", "yes"],
+                vec!["0.75x", "4h", "-1h", "00:00 (+1d)", "no"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_comparison_rows_breaks() {
+        assert_eq!(
+            rows("1h", "1x,2x", Some(BreakPolicy::POMODORO)),
+            vec![
+                vec![
+                    "Speed",
+                    "Finishes in",
+                    "Session",
+                    "Saved",
+                    "Done at",
+                    "Fits today"
+                ],
+                vec!["1x", "1h", "1h10m", "0s", "21:10", "yes"],
+                vec!["2x", "30m", "35m", "30m", "20:35", "yes"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_comparison_rows_invalid() {
+        let range = MultiplierRange::default();
+        let error = |multipliers| {
+            comparison_rows(
+                "1h",
+                multipliers,
+                &range,
+                &day(),
+                None,
+                &TimeFormat::default(),
+            )
+            .unwrap_err()
+            .to_string()
+        };
+
+        assert!(error("1.5,fast").contains("(fast)"));
+        assert!(error("2x,,3x").contains("()"));
+        assert!(error("1.5,500x").contains("(500x)"));
+    }
+}
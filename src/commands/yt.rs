@@ -1,59 +1,62 @@
 use clap::Args;
 
 use crate::{
-    cli::flags::Flags,
+    cli::{flags::Flags, selection::Selection},
     commands::{Runnable, trim::TrimCmd},
     core::{
-        api::ApiClientManager,
         style::Style,
-        time::parse_time,
-        utils::choose_or_grab_link,
-        youtils::{get_youtube_api_key, get_youtube_id},
+        utils::{choose_or_grab_links, print_subtotals},
     },
 };
-use anyhow::{Result, bail};
+use anyhow::Result;
 
 #[derive(Debug, Default, Args)]
 pub struct YtCmd {
-    /// The URL, or link, for the YouTube video.
-    #[arg(required_unless_present = "clip")]
-    link: Option<String>,
+    /// The URLs, or links, for YouTube videos, playlists or channels. Plain durations (e.g. 45m) can be mixed in, and - reads more from stdin.
+    #[arg(required_unless_present_any = ["clip", "from_file"])]
+    sources: Vec<String>,
 
-    /// The multiplier (e.g. 1.25x, 1.25).
+    /// The multiplier (e.g. 1.25x, 1.25). Pass several comma-separated multipliers (e.g. 1.25,1.5,2) to compare them.
     #[arg(short, long)]
     multiplier: String,
 
-    /// Max amount of items to traverse in a playlist (if one is passed). Defaults to the total length of the playlist.
-    #[arg(visible_alias = "max", long, default_value = "0")]
-    max_items: usize,
+    #[command(flatten)]
+    selection: Selection,
 }
 
 impl Runnable for YtCmd {
     fn run(self, flags: &Flags, style: &Style) -> Result<()> {
-        let key = get_youtube_api_key()?;
-        let link = choose_or_grab_link(self.link, flags.clip)?;
-
-        let manager = ApiClientManager::new(&key);
-        let id = get_youtube_id(&link);
-
-        if let Some(id) = id {
-            match manager.fetch_duration_from_id(&id, self.max_items) {
-                Ok((duration, item_count)) => {
-                    let cmd = TrimCmd {
-                        duration: parse_time(duration),
-                        multiplier: self.multiplier,
-                    };
-
-                    cmd.run(flags, style)?;
-                    if id.is_playlist {
-                        println!("Trimmed for {item_count} item(s).")
-                    }
-                }
-                Err(e) => bail!("Failed to fetch details from URL: {e}"),
-            }
-        } else {
-            bail!(
-                "Not a valid YouTube URL! Only videos/embeds/shorts URLs are supported in the `yt` command."
+        let sources = choose_or_grab_links(
+            self.sources,
+            self.selection.from_file.as_deref(),
+            flags.clip,
+            self.selection.first,
+        )?;
+        let tally = self.selection.tally(&sources, flags, style)?;
+
+        print_subtotals(&tally, &flags.format);
+
+        let cmd = TrimCmd {
+            duration: tally.total().to_string(),
+            multiplier: self.multiplier,
+        };
+        cmd.run(flags, style)?;
+
+        if tally.items() > 1 {
+            println!("Trimmed for {} item(s).", tally.items())
+        }
+        if tally.duplicates() > 0 {
+            println!(
+                "Skipped {} video(s) already counted by an earlier source.",
+                tally.duplicates()
+            )
+        }
+        if let [subtotal] = tally.subtotals.as_slice()
+            && let Some(start) = subtotal.start
+        {
+            println!(
+                "Counted from {} into the video (pass --from-start to count all of it).",
+                flags.format.format(start)
             )
         }
 
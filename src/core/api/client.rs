@@ -1,21 +1,35 @@
-use std::collections::HashSet;
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+};
 
-use reqwest::blocking::Client;
+use reqwest::{StatusCode, blocking::Client};
+use serde::de::DeserializeOwned;
 
 use crate::{
     core::{
-        api::types::{YTPlaylistItems, YTPlaylistList, YTVideos, YTVideosItem},
-        time::parse_duration,
-        youtils::YoutubeId,
+        api::types::{
+            YTChannels, YTErrorResponse, YTPlaylistItems, YTPlaylistList, YTVideoSnippet, YTVideos,
+            YTVideosContentDetails, YTVideosItem,
+        },
+        cache::{Cache, CachedPlaylist, CachedPlaylistItem, CachedVideo},
+        duration::Duration,
+        youtils::{ChannelRef, ItemRange, PublishedRange, YoutubeId},
     },
-    errors::TYoutubeError,
+    errors::{TYoutubeError, TYoutubeWarning},
 };
 
-const API_BASE: &str = "https://www.googleapis.com/youtube/v3";
+/// The YouTube Data API, unless another base URL is configured.
+pub const DEFAULT_API_BASE: &str = "https://www.googleapis.com/youtube/v3";
 
 pub struct ApiClientManager<'a> {
     client: Client,
     key: &'a str,
+    base: String,
+    published: PublishedRange,
+    warnings: RefCell<Vec<TYoutubeWarning>>,
+    cache: Option<RefCell<Cache>>,
+    offline: Cell<bool>,
 }
 
 impl<'a> ApiClientManager<'a> {
@@ -24,159 +38,456 @@ impl<'a> ApiClientManager<'a> {
         Self {
             client: Client::new(),
             key,
+            base: DEFAULT_API_BASE.to_string(),
+            published: PublishedRange::default(),
+            warnings: RefCell::default(),
+            cache: None,
+            offline: Cell::new(false),
+        }
+    }
+
+    /// Returns the same manager, sending requests to another base URL (e.g. a proxy or a mock server).
+    #[must_use]
+    pub fn with_base(self, base: &str) -> Self {
+        Self {
+            base: base.trim_end_matches('/').to_string(),
+            ..self
+        }
+    }
+
+    /// Returns the same manager, only taking playlist and channel videos published within the given range.
+    #[must_use]
+    pub fn with_published(self, published: PublishedRange) -> Self {
+        Self { published, ..self }
+    }
+
+    /// Returns the same manager, answering from (and storing responses in) the given cache where possible.
+    #[must_use]
+    pub fn with_cache(self, cache: Option<Cache>) -> Self {
+        Self {
+            cache: cache.map(RefCell::new),
+            ..self
+        }
+    }
+
+    /// Returns the same manager, answering only from the cache (regardless of its age) if `offline` is set.
+    #[must_use]
+    pub fn with_offline(self, offline: bool) -> Self {
+        if offline && let Some(cache) = &self.cache {
+            cache.borrow_mut().allow_stale();
+        }
+
+        Self {
+            offline: Cell::new(offline),
+            ..self
+        }
+    }
+
+    /// Whether the results come from the cache only, either since the manager is offline or since a request
+    /// failed.
+    #[must_use]
+    pub fn is_offline(&self) -> bool {
+        self.offline.get()
+    }
+
+    /// Switches to answering from the cache only, after a request failed with the given error.
+    fn go_offline(&self, error: &TYoutubeError) {
+        self.offline.set(true);
+        if let Some(cache) = &self.cache {
+            cache.borrow_mut().allow_stale();
+        }
+        self.warn(TYoutubeWarning::RequestFailed(match error {
+            TYoutubeError::Unavailable((status, _)) => Some(*status),
+            _ => None,
+        }));
+    }
+
+    /// Answers from the cache or else runs the request. When offline, only the cache is used. If YouTube
+    /// can't be reached or can't answer right now and there is a cache to fall back to, the manager goes
+    /// offline (and the error stands if the cache doesn't have it either).
+    fn fetch_or_cached<T>(
+        &self,
+        lookup: impl Fn(&Cache) -> Option<T>,
+        fetch: impl FnOnce() -> Result<T, TYoutubeError>,
+        not_cached: impl FnOnce() -> TYoutubeError,
+    ) -> Result<T, TYoutubeError> {
+        if let Some(value) = self.cached(&lookup) {
+            return Ok(value);
+        }
+
+        if !self.offline.get() {
+            return match fetch() {
+                Err(e) if e.is_unavailable() && self.cache.is_some() => {
+                    self.go_offline(&e);
+                    self.cached(&lookup).ok_or(e)
+                }
+                result => result,
+            };
+        }
+
+        self.cached(&lookup).ok_or_else(not_cached)
+    }
+
+    /// Sends a request to an endpoint of the API. Error responses are reported along with their message, as
+    /// [`TYoutubeError::Unavailable`] if they mean the API can't answer right now.
+    fn request<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        query: &str,
+    ) -> Result<T, TYoutubeError> {
+        let url = format!("{}/{endpoint}?{query}&key={}", self.base, self.key);
+
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .map_err(|_| TYoutubeError::Reqwest)?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error = response.json::<YTErrorResponse>().ok().map(|r| r.error);
+            let limited = error.as_ref().is_some_and(|e| {
+                e.errors.iter().any(|d| {
+                    matches!(
+                        d.reason.as_str(),
+                        "quotaExceeded"
+                            | "dailyLimitExceeded"
+                            | "rateLimitExceeded"
+                            | "userRateLimitExceeded"
+                    )
+                })
+            });
+
+            let details = (status.as_u16(), error.map(|e| e.message));
+            return Err(
+                if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS || limited {
+                    TYoutubeError::Unavailable(details)
+                } else {
+                    TYoutubeError::Api(details)
+                },
+            );
+        }
+
+        response
+            .json()
+            .map_err(|_| TYoutubeError::ResponseBodyParseFailure)
+    }
+
+    /// Looks something up in the cache, if there is one.
+    fn cached<T>(&self, lookup: impl FnOnce(&Cache) -> Option<T>) -> Option<T> {
+        self.cache.as_ref().and_then(|c| lookup(&c.borrow()))
+    }
+
+    /// Stores fetched responses in the cache (if there is one) and writes it, recording a warning on failure.
+    fn update_cache(&self, update: impl FnOnce(&mut Cache)) {
+        let Some(cache) = &self.cache else {
+            return;
+        };
+
+        let mut cache = cache.borrow_mut();
+        update(&mut cache);
+
+        if let Err(e) = cache.save() {
+            self.warn(TYoutubeWarning::CacheNotSaved(e.to_string()));
+        }
+    }
+
+    /// Records a warning, to be reported along with those of the requests (see [`Self::take_warnings`]).
+    pub fn warn(&self, warning: TYoutubeWarning) {
+        self.warnings.borrow_mut().push(warning);
+    }
+
+    /// Returns (and clears) the warnings collected by the requests made so far. When offline, this ends with
+    /// a note on the age of the cached data used.
+    pub fn take_warnings(&self) -> Vec<TYoutubeWarning> {
+        let mut warnings = self.warnings.take();
+
+        if self.offline.get() {
+            warnings.push(TYoutubeWarning::Offline(self.cached(|c| c.oldest_used())));
         }
+        warnings
+    }
+
+    /// Resolves a channel to the ID of the playlist holding all of its uploads.
+    pub fn uploads_playlist(&self, channel: &ChannelRef) -> Result<String, TYoutubeError> {
+        let key = channel.to_string();
+
+        self.fetch_or_cached(
+            |c| c.channel(&key).map(str::to_string),
+            || self.fetch_uploads_playlist(channel),
+            || TYoutubeError::NotCached(format!("channel {key}")),
+        )
+    }
+
+    fn fetch_uploads_playlist(&self, channel: &ChannelRef) -> Result<String, TYoutubeError> {
+        let key = channel.to_string();
+        let (param, value) = match channel {
+            ChannelRef::Id(id) => ("id", id),
+            ChannelRef::Handle(handle) => ("forHandle", handle),
+            ChannelRef::Username(name) => ("forUsername", name),
+        };
+
+        let response: YTChannels =
+            self.request("channels", &format!("part=contentDetails&{param}={value}"))?;
+
+        let uploads = response
+            .items
+            .into_iter()
+            .next()
+            .map(|c| c.content_details.related_playlists.uploads)
+            .ok_or_else(|| TYoutubeError::ChannelNotFound(key.clone()))?;
+
+        self.update_cache(|c| c.insert_channel(&key, &uploads));
+        Ok(uploads)
     }
 
     /// Returns a vector of IDs from a single YouTube ID.
     ///
-    /// This is expected to be used for fetching the contents of a playlist (or "video IDs"). If the [`YoutubeId`] object
-    /// is not a playlist, then a vector would be returned with the ID that was originally passed in.
+    /// This is expected to be used for fetching the contents of a playlist or channel (or "video IDs"), limited
+    /// to the positions in `items`. If the range reaches past the end of the playlist, it is cut short and a
+    /// warning is recorded (see [`Self::take_warnings`]); if nothing is left of it, no items are fetched at all. If the [`YoutubeId`] object is a single video, then a
+    /// vector would be returned with the ID that was originally passed in.
     pub fn expand_id(
         &self,
         id: &YoutubeId,
-        max_items: usize,
+        items: ItemRange,
     ) -> Result<Vec<String>, TYoutubeError> {
-        let total_ids = {
-            let mut next_tok: Option<String> = None;
-            let mut ids = Vec::new();
-            let mut seen_tokens: HashSet<String> = HashSet::new();
-
-            if id.is_playlist {
-                let url = format!(
-                    "{API_BASE}/playlists?part=contentDetails&id={}&key={}&maxResults=1",
-                    &id.id, self.key
-                );
-
-                let response: YTPlaylistList = self
-                    .client
-                    .get(url)
-                    .send()
-                    .map_err(|_| TYoutubeError::Reqwest)?
-                    .json()
-                    .map_err(|_| TYoutubeError::ResponseBodyParseFailure)?;
-
-                let traversible_items = if let Some(ic) = response.items.first() {
-                    let max_traversible = ic.content_details.item_count;
-
-                    if max_items != 0 {
-                        if max_items > max_traversible {
-                            return Err(TYoutubeError::InvalidMaxSize((
-                                max_items,
-                                max_traversible,
-                            )));
-                        } else {
-                            max_items
-                        }
-                    } else {
-                        max_traversible
-                    }
-                } else {
-                    return Err(TYoutubeError::InvalidPlaylist(id.id.clone()));
+        let playlist = match id {
+            YoutubeId::Video { id, .. } => return Ok(vec![id.clone()]),
+            YoutubeId::Playlist { id, .. } => id.clone(),
+            YoutubeId::Channel(channel) => self.uploads_playlist(channel)?,
+        };
+
+        let fetched = self.fetch_or_cached(
+            |c| {
+                c.playlist(&playlist)
+                    .filter(|p| {
+                        let (positions, _) = items.clamp(p.item_count);
+                        positions.is_empty() || p.covers(positions.end)
+                    })
+                    .cloned()
+            },
+            || {
+                let item_count = self.fetch_item_count(&playlist)?;
+                let (positions, _) = items.clamp(item_count);
+                if positions.is_empty() {
+                    return Ok(CachedPlaylist {
+                        item_count,
+                        items: Vec::new(),
+                        complete: false,
+                    });
+                }
+
+                let (fetched_items, complete) =
+                    self.fetch_playlist_items(&playlist, positions.end)?;
+                let fetched = CachedPlaylist {
+                    item_count,
+                    items: fetched_items,
+                    complete,
                 };
 
-                for start in (0..traversible_items).step_by(50) {
-                    let max_results = (traversible_items - start).min(50);
-
-                    let url = format!(
-                        "{API_BASE}/playlistItems?playlistId={}&key={}&maxResults={}&part=contentDetails{}",
-                        &id.id,
-                        self.key,
-                        max_results,
-                        if let Some(ref tok) = next_tok {
-                            format!("&pageToken={tok}")
-                        } else {
-                            "".to_string()
-                        }
-                    );
+                self.update_cache(|c| c.insert_playlist(&playlist, fetched.clone()));
+                Ok(fetched)
+            },
+            || TYoutubeError::NotCached(format!("playlist {playlist}")),
+        )?;
 
-                    let response: YTPlaylistItems = self
-                        .client
-                        .get(url)
-                        .send()
-                        .map_err(|_| TYoutubeError::Reqwest)?
-                        .json()
-                        .map_err(|_| TYoutubeError::ResponseBodyParseFailure)?;
-
-                    if let Some(t) = &response.next_page_token
-                        && seen_tokens.contains(t)
-                    {
-                        break;
-                    }
+        let (positions, clamped) = items.clamp(fetched.item_count);
+
+        if clamped {
+            self.warn(TYoutubeWarning::RangeClamped((
+                positions.start + 1,
+                fetched.item_count,
+            )));
+        }
 
-                    let current_ids = response
-                        .items
-                        .into_iter()
-                        .map(|f| f.content_details.video_id);
+        Ok(fetched
+            .items
+            .into_iter()
+            .take(positions.end)
+            .skip(positions.start)
+            .filter(|f| self.published.contains(f.published_at.as_deref()))
+            .map(|f| f.video_id)
+            .collect())
+    }
+
+    /// Fetches the number of items in a playlist.
+    fn fetch_item_count(&self, playlist: &str) -> Result<usize, TYoutubeError> {
+        let response: YTPlaylistList = self.request(
+            "playlists",
+            &format!("part=contentDetails&id={playlist}&maxResults=1"),
+        )?;
+
+        response
+            .items
+            .first()
+            .map(|ic| ic.content_details.item_count)
+            .ok_or_else(|| TYoutubeError::InvalidPlaylist(playlist.to_string()))
+    }
 
-                    ids.extend(current_ids);
+    /// Fetches the first `end` items of a playlist, in order, along with whether the last page was reached.
+    ///
+    /// The API can only page from the start, so everything before a range has to be fetched as well.
+    fn fetch_playlist_items(
+        &self,
+        playlist: &str,
+        end: usize,
+    ) -> Result<(Vec<CachedPlaylistItem>, bool), TYoutubeError> {
+        let mut next_tok: Option<String> = None;
+        let mut items = Vec::new();
+        let mut seen_tokens: HashSet<String> = HashSet::new();
+        let mut complete = false;
+
+        for start in (0..end).step_by(50) {
+            let max_results = (end - start).min(50);
+
+            let query = format!(
+                "playlistId={playlist}&maxResults={}&part=contentDetails{}",
+                max_results,
+                if let Some(ref tok) = next_tok {
+                    format!("&pageToken={tok}")
+                } else {
+                    "".to_string()
+                }
+            );
 
-                    if let Some(new_tok) = response.next_page_token {
-                        next_tok = Some(new_tok.clone());
-                        seen_tokens.insert(new_tok);
-                    } else {
-                        break;
+            let response: YTPlaylistItems = self.request("playlistItems", &query)?;
+
+            items.extend(response.items.into_iter().map(|f| CachedPlaylistItem {
+                video_id: f.content_details.video_id,
+                published_at: f.content_details.video_published_at,
+            }));
+
+            // a token that was already followed would only go around in circles
+            match response.next_page_token {
+                Some(new_tok) if seen_tokens.insert(new_tok.clone()) => next_tok = Some(new_tok),
+                Some(_) => break,
+                None => {
+                    complete = true;
+                    break;
+                }
+            }
+        }
+
+        Ok((items, complete))
+    }
+
+    /// Fetches the details of the given videos, in order and without duplicates. Videos that are not found
+    /// (e.g. private or deleted ones) are left out.
+    pub fn fetch_video_items(&self, ids: &[String]) -> Result<Vec<YTVideosItem>, TYoutubeError> {
+        let mut seen = HashSet::new();
+        let ids: Vec<&String> = ids.iter().filter(|id| seen.insert(*id)).collect();
+
+        let mut cached: HashMap<String, CachedVideo> = HashMap::new();
+        let mut missing: Vec<String> = Vec::new();
+        for id in &ids {
+            match self.cached(|c| c.video(id).cloned()) {
+                Some(video) => {
+                    cached.insert(id.to_string(), video);
+                }
+                None => missing.push(id.to_string()),
+            }
+        }
+
+        let mut fetched: HashMap<String, YTVideosItem> = HashMap::new();
+
+        if !missing.is_empty() && !self.offline.get() {
+            match self.fetch_videos(&missing) {
+                Ok(videos) => fetched = videos,
+                Err(e) if e.is_unavailable() && self.cache.is_some() => self.go_offline(&e),
+                Err(e) => return Err(e),
+            }
+        }
+
+        if self.offline.get() {
+            // older copies of what wasn't fresh may still be cached
+            let mut left_out = 0;
+            for id in &missing {
+                match self.cached(|c| c.video(id).cloned()) {
+                    Some(video) => {
+                        cached.insert(id.to_string(), video);
                     }
+                    None => left_out += 1,
                 }
-            } else {
-                ids.push(id.id.clone());
             }
 
-            ids
-        };
+            if left_out > 0 {
+                self.warn(TYoutubeWarning::NotCached(left_out));
+            }
+        }
 
-        Ok(total_ids)
+        Ok(ids
+            .into_iter()
+            .filter_map(|id| {
+                fetched.remove(id).or_else(|| {
+                    cached.remove(id).map(|video| YTVideosItem {
+                        id: id.clone(),
+                        snippet: YTVideoSnippet {
+                            title: video.title,
+                            description: String::new(),
+                        },
+                        content_details: YTVideosContentDetails {
+                            duration: video.duration,
+                        },
+                    })
+                })
+            })
+            .collect())
     }
 
-    /// Fetches a single chunk of video items.
-    pub fn fetch_video_items(&self, ids: &[String]) -> Result<Vec<YTVideosItem>, TYoutubeError> {
-        let mut vector: Vec<YTVideosItem> = Vec::new();
+    /// Fetches the given videos from the API, keyed by their ID, and caches them.
+    fn fetch_videos(&self, ids: &[String]) -> Result<HashMap<String, YTVideosItem>, TYoutubeError> {
+        let mut fetched: HashMap<String, YTVideosItem> = HashMap::new();
 
         for chunk_ids in ids.chunks(50) {
-            let url = format!(
-                "{API_BASE}/videos?id={}&key={}&part=snippet,contentDetails",
-                chunk_ids.join(","),
-                self.key
-            );
+            let response: YTVideos = self.request(
+                "videos",
+                &format!("id={}&part=snippet,contentDetails", chunk_ids.join(",")),
+            )?;
 
-            let mut response: YTVideos = self
-                .client
-                .get(url)
-                .send()
-                .map_err(|_| TYoutubeError::Reqwest)?
-                .json()
-                .map_err(|_| TYoutubeError::ResponseBodyParseFailure)?;
+            fetched.extend(response.items.into_iter().map(|v| (v.id.clone(), v)));
+        }
 
-            vector.append(&mut response.items);
+        if !fetched.is_empty() {
+            self.update_cache(|c| {
+                for (id, video) in &fetched {
+                    c.insert_video(
+                        id,
+                        CachedVideo {
+                            title: video.snippet.title.clone(),
+                            duration: video.content_details.duration.clone(),
+                        },
+                    );
+                }
+            });
         }
 
-        Ok(vector)
+        Ok(fetched)
     }
 
-    /// Fetches the total duration from a single YouTube ID. The ID could be of either a playlist or a video.
+    /// Fetches the total duration from a single YouTube ID. The ID could be of a video, a playlist or a channel.
+    ///
+    /// For a video link with a timestamp, only the part after it is counted. The number of videos returned leaves
+    /// out those that weren't found (see [`Self::fetch_video_items`]).
     pub fn fetch_duration_from_id(
         &self,
         id: &YoutubeId,
-        max_items: usize,
-    ) -> Result<(f64, usize), TYoutubeError> {
-        let total_ids = self.expand_id(id, max_items)?;
+        items: ItemRange,
+    ) -> Result<(Duration, usize), TYoutubeError> {
+        let total_ids = self.expand_id(id, items)?;
         let fetched_items = self.fetch_video_items(&total_ids)?;
 
-        let total_duration: f64 = fetched_items
+        let total_duration: Duration = fetched_items
             .iter()
             .map(|f| {
-                let (dur, _) = parse_duration(
-                    f.content_details
-                        .duration
-                        .to_lowercase()
-                        .trim_start_matches("pt"),
-                )
-                .unwrap_or((0.0, 0));
-                dur
+                f.content_details
+                    .duration
+                    .parse::<Duration>()
+                    .unwrap_or(Duration::ZERO)
             })
-            .collect::<Vec<f64>>()
-            .iter()
-            .sum();
+            .sum::<Duration>()
+            .saturating_sub(id.start().unwrap_or(Duration::ZERO));
 
-        Ok((total_duration, total_ids.len()))
+        Ok((total_duration, fetched_items.len()))
     }
 }
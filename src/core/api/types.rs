@@ -9,6 +9,7 @@ pub struct YTVideos {
 
 #[derive(Debug, Deserialize)]
 pub struct YTVideosItem {
+    pub id: String,
     #[allow(unused)] // TODO: remove these once the awaited feature has been added
     pub snippet: YTVideoSnippet,
     #[serde(rename = "contentDetails")]
@@ -66,4 +67,52 @@ pub struct YTPlaylistItemsItem {
 pub struct YTPlaylistItemsItemContentDetails {
     #[serde(rename = "videoId")]
     pub video_id: String,
+    /// Missing for private and deleted videos.
+    #[serde(rename = "videoPublishedAt")]
+    pub video_published_at: Option<String>,
+}
+
+// channels
+
+#[derive(Debug, Deserialize)]
+pub struct YTChannels {
+    /// Omitted entirely when no channel matches.
+    #[serde(default)]
+    pub items: Vec<YTChannelsItem>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct YTChannelsItem {
+    #[serde(rename = "contentDetails")]
+    pub content_details: YTChannelsItemContentDetails,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct YTChannelsItemContentDetails {
+    #[serde(rename = "relatedPlaylists")]
+    pub related_playlists: YTChannelsRelatedPlaylists,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct YTChannelsRelatedPlaylists {
+    pub uploads: String,
+}
+
+// errors
+
+#[derive(Debug, Deserialize)]
+pub struct YTErrorResponse {
+    pub error: YTError,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct YTError {
+    pub message: String,
+    #[serde(default)]
+    pub errors: Vec<YTErrorDetail>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct YTErrorDetail {
+    pub reason: String,
 }
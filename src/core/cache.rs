@@ -0,0 +1,430 @@
+//! A persistent cache of YouTube API responses, so that looking up the same playlist again costs no quota.
+
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::{core::duration::Duration, errors::TCacheError};
+
+/// How long cached responses are used before being fetched again, unless configured otherwise.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(24 * 3600);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedVideo {
+    pub title: String,
+    /// The duration as returned by the API, in ISO 8601.
+    pub duration: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedPlaylistItem {
+    pub video_id: String,
+    pub published_at: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedPlaylist {
+    /// The length of the whole playlist.
+    pub item_count: usize,
+    /// The items from the start of the playlist, which are only all of them if the whole playlist was fetched.
+    pub items: Vec<CachedPlaylistItem>,
+    /// Whether paging reached the end of the playlist. There may still be fewer items than `item_count`, as
+    /// the count includes e.g. private and deleted videos that aren't listed.
+    #[serde(default)]
+    pub complete: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry<T> {
+    /// When the response was fetched, as a UNIX timestamp.
+    fetched_at: i64,
+    value: T,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheData {
+    #[serde(default)]
+    videos: HashMap<String, Entry<CachedVideo>>,
+    #[serde(default)]
+    playlists: HashMap<String, Entry<CachedPlaylist>>,
+    /// The uploads playlists of channels.
+    #[serde(default)]
+    channels: HashMap<String, Entry<String>>,
+}
+
+/// Counts of the cached entries, as shown by `ts cache stats`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CacheStats {
+    pub videos: usize,
+    pub playlists: usize,
+    pub channels: usize,
+    /// The number of entries older than the TTL, which are fetched again on their next use.
+    pub expired: usize,
+    /// The age of the oldest entry.
+    pub oldest: Option<Duration>,
+}
+
+/// The cache file, loaded into memory.
+///
+/// Entries older than the TTL are ignored, as are all entries when refreshing; they are replaced (and the file
+/// written) as soon as new responses come in. Once stale entries are allowed (e.g. when offline), every entry is
+/// used regardless of its age.
+#[derive(Debug)]
+pub struct Cache {
+    path: PathBuf,
+    data: CacheData,
+    ttl: Duration,
+    refresh: bool,
+    stale_ok: bool,
+    now: i64,
+    dirty: bool,
+    /// When the oldest entry looked up so far was fetched.
+    oldest_used: Cell<Option<i64>>,
+}
+
+impl Cache {
+    /// The path of the cache file, under the platform's cache directory (e.g. `~/.cache/trimsec` on Linux).
+    pub fn file() -> Result<PathBuf, TCacheError> {
+        dirs::cache_dir()
+            .map(|d| d.join("trimsec").join("cache.json"))
+            .ok_or(TCacheError::NoCacheDir)
+    }
+
+    /// Loads the cache file. A missing file results in an empty cache, while one that can't be read or parsed
+    /// is reported (rather than replaced once saved).
+    pub fn open(ttl: Duration, refresh: bool) -> Result<Self, TCacheError> {
+        Self::open_at(Self::file()?, ttl, refresh)
+    }
+
+    fn open_at(path: PathBuf, ttl: Duration, refresh: bool) -> Result<Self, TCacheError> {
+        let data = match fs::read_to_string(&path) {
+            Ok(d) => {
+                serde_json::from_str(&d).map_err(|e| TCacheError::ReadFailed(e.to_string()))?
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => CacheData::default(),
+            Err(e) => return Err(TCacheError::ReadFailed(e.to_string())),
+        };
+
+        Ok(Self {
+            path,
+            data,
+            ttl,
+            refresh,
+            stale_ok: false,
+            now: Utc::now().timestamp(),
+            dirty: false,
+            oldest_used: Cell::default(),
+        })
+    }
+
+    /// Deletes the cache file, returning whether there was one.
+    pub fn clear() -> Result<bool, TCacheError> {
+        let path = Self::file()?;
+
+        match fs::remove_file(&path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(TCacheError::WriteFailed(e.to_string())),
+        }
+    }
+
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn age(&self, fetched_at: i64) -> Duration {
+        Duration::from_secs(u64::try_from(self.now.saturating_sub(fetched_at)).unwrap_or(0))
+    }
+
+    fn is_fresh(&self, fetched_at: i64) -> bool {
+        self.stale_ok || (!self.refresh && self.age(fetched_at) < self.ttl)
+    }
+
+    fn lookup<'a, T>(&self, entries: &'a HashMap<String, Entry<T>>, key: &str) -> Option<&'a T> {
+        let entry = entries.get(key).filter(|e| self.is_fresh(e.fetched_at))?;
+
+        let oldest = self
+            .oldest_used
+            .get()
+            .map_or(entry.fetched_at, |o| o.min(entry.fetched_at));
+        self.oldest_used.set(Some(oldest));
+
+        Some(&entry.value)
+    }
+
+    /// Uses all entries from now on, regardless of their age and of refreshing.
+    pub fn allow_stale(&mut self) {
+        self.stale_ok = true;
+    }
+
+    /// The age of the oldest entry looked up so far, i.e. how out of date the answers may be.
+    #[must_use]
+    pub fn oldest_used(&self) -> Option<Duration> {
+        self.oldest_used.get().map(|o| self.age(o))
+    }
+
+    fn entry<T>(&mut self, value: T) -> Entry<T> {
+        self.dirty = true;
+        Entry {
+            fetched_at: self.now,
+            value,
+        }
+    }
+
+    #[must_use]
+    pub fn video(&self, id: &str) -> Option<&CachedVideo> {
+        self.lookup(&self.data.videos, id)
+    }
+
+    pub fn insert_video(&mut self, id: &str, video: CachedVideo) {
+        let entry = self.entry(video);
+        self.data.videos.insert(id.to_string(), entry);
+    }
+
+    #[must_use]
+    pub fn playlist(&self, id: &str) -> Option<&CachedPlaylist> {
+        self.lookup(&self.data.playlists, id)
+    }
+
+    pub fn insert_playlist(&mut self, id: &str, playlist: CachedPlaylist) {
+        let entry = self.entry(playlist);
+        self.data.playlists.insert(id.to_string(), entry);
+    }
+
+    /// The uploads playlist of a channel, keyed by how the channel was referenced (e.g. `@handle`).
+    #[must_use]
+    pub fn channel(&self, channel: &str) -> Option<&str> {
+        self.lookup(&self.data.channels, channel)
+            .map(String::as_str)
+    }
+
+    pub fn insert_channel(&mut self, channel: &str, uploads: &str) {
+        let entry = self.entry(uploads.to_string());
+        self.data.channels.insert(channel.to_string(), entry);
+    }
+
+    /// Writes the cache file, if anything changed since it was loaded, leaving out expired entries. These are
+    /// kept while stale entries are allowed, since they may be all there is to answer from.
+    ///
+    /// The file is written next to the old one and then moved into its place, so that an interrupted run
+    /// can't leave a truncated cache behind.
+    pub fn save(&mut self) -> Result<(), TCacheError> {
+        if !self.dirty {
+            return Ok(());
+        }
+
+        if !self.stale_ok {
+            self.prune();
+        }
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| TCacheError::WriteFailed(e.to_string()))?;
+        }
+        let data = serde_json::to_string(&self.data)
+            .map_err(|e| TCacheError::WriteFailed(e.to_string()))?;
+
+        let temp = self
+            .path
+            .with_extension(format!("json.{}.tmp", std::process::id()));
+        fs::write(&temp, data)
+            .and_then(|()| fs::rename(&temp, &self.path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&temp);
+                TCacheError::WriteFailed(e.to_string())
+            })?;
+
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Drops the entries older than the TTL.
+    fn prune(&mut self) {
+        let (now, ttl) = (self.now, self.ttl);
+        let fresh = |fetched_at: i64| {
+            u64::try_from(now.saturating_sub(fetched_at)).unwrap_or(0) < ttl.as_secs()
+        };
+
+        self.data.videos.retain(|_, e| fresh(e.fetched_at));
+        self.data.playlists.retain(|_, e| fresh(e.fetched_at));
+        self.data.channels.retain(|_, e| fresh(e.fetched_at));
+    }
+
+    #[must_use]
+    pub fn stats(&self) -> CacheStats {
+        let fetched: Vec<i64> = (self.data.videos.values().map(|e| e.fetched_at))
+            .chain(self.data.playlists.values().map(|e| e.fetched_at))
+            .chain(self.data.channels.values().map(|e| e.fetched_at))
+            .collect();
+
+        CacheStats {
+            videos: self.data.videos.len(),
+            playlists: self.data.playlists.len(),
+            channels: self.data.channels.len(),
+            expired: fetched.iter().filter(|f| self.age(**f) >= self.ttl).count(),
+            oldest: fetched.iter().min().map(|f| self.age(*f)),
+        }
+    }
+}
+
+impl CachedPlaylist {
+    /// Whether enough items are cached to take the first `end` positions of the playlist.
+    #[must_use]
+    pub fn covers(&self, end: usize) -> bool {
+        self.complete || self.items.len() >= end.min(self.item_count)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn cache(ttl: u64, refresh: bool) -> Cache {
+        Cache {
+            path: PathBuf::from("cache.json"),
+            data: CacheData::default(),
+            ttl: Duration::from_secs(ttl),
+            refresh,
+            stale_ok: false,
+            now: 1_000_000,
+            dirty: false,
+            oldest_used: Cell::default(),
+        }
+    }
+
+    fn video(title: &str) -> CachedVideo {
+        CachedVideo {
+            title: title.to_string(),
+            duration: "PT1M".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_expiry() {
+        let mut cache = cache(3600, false);
+        cache.insert_video("fresh", video("Fresh"));
+        cache.insert_video("old", video("Old"));
+        cache.insert_channel("@somechannel", "UUsomechannel");
+        if let Some(e) = cache.data.videos.get_mut("old") {
+            e.fetched_at -= 3600;
+        }
+
+        assert!(cache.dirty);
+        assert_eq!(cache.video("fresh"), Some(&video("Fresh")));
+        assert_eq!(cache.video("old"), None);
+        assert_eq!(cache.video("missing"), None);
+        assert_eq!(cache.channel("@somechannel"), Some("UUsomechannel"));
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                videos: 2,
+                playlists: 0,
+                channels: 1,
+                expired: 1,
+                oldest: Some(Duration::from_secs(3600)),
+            }
+        );
+
+        cache.refresh = true;
+        assert_eq!(cache.video("fresh"), None);
+    }
+
+    #[test]
+    fn test_stale() {
+        let mut cache = cache(3600, true);
+        cache.insert_video("fresh", video("Fresh"));
+        cache.insert_video("old", video("Old"));
+        if let Some(e) = cache.data.videos.get_mut("old") {
+            e.fetched_at -= 7200;
+        }
+
+        assert_eq!(cache.video("old"), None);
+        assert_eq!(cache.oldest_used(), None);
+
+        cache.allow_stale();
+        assert_eq!(cache.video("fresh"), Some(&video("Fresh")));
+        assert_eq!(cache.oldest_used(), Some(Duration::ZERO));
+        assert_eq!(cache.video("old"), Some(&video("Old")));
+        assert_eq!(cache.oldest_used(), Some(Duration::from_secs(7200)));
+    }
+
+    #[test]
+    fn test_save() {
+        let dir = std::env::temp_dir().join(format!("trimsec-cache-test-{}", std::process::id()));
+        let mut cache = Cache {
+            path: dir.join("cache.json"),
+            ..cache(3600, false)
+        };
+        cache.insert_video("fresh", video("Fresh"));
+        cache.insert_video("old", video("Old"));
+        if let Some(e) = cache.data.videos.get_mut("old") {
+            e.fetched_at -= 3600;
+        }
+        cache.save().unwrap();
+
+        let saved: CacheData =
+            serde_json::from_str(&fs::read_to_string(cache.path()).unwrap()).unwrap();
+        let files = fs::read_dir(&dir).unwrap().count();
+        let _ = fs::remove_dir_all(&dir);
+
+        // expired entries are pruned, and no temporary file is left behind
+        assert_eq!(files, 1);
+        assert!(saved.videos.contains_key("fresh"));
+        assert!(!saved.videos.contains_key("old"));
+        assert!(!cache.dirty);
+    }
+
+    #[test]
+    fn test_open() {
+        let dir = std::env::temp_dir().join(format!("trimsec-open-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("cache.json");
+        let ttl = Duration::from_secs(3600);
+
+        let missing = Cache::open_at(path.clone(), ttl, false);
+        fs::write(&path, "{garbage").unwrap();
+        let corrupt = Cache::open_at(path.clone(), ttl, false);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(missing.unwrap().stats(), CacheStats::default());
+        assert!(matches!(corrupt, Err(TCacheError::ReadFailed(_))));
+    }
+
+    #[test]
+    fn test_playlist_coverage() {
+        let item = |id: &str| CachedPlaylistItem {
+            video_id: id.to_string(),
+            published_at: None,
+        };
+        let playlist = CachedPlaylist {
+            item_count: 3,
+            items: vec![item("a"), item("b")],
+            complete: false,
+        };
+
+        assert!(playlist.covers(2));
+        assert!(!playlist.covers(3));
+
+        // the API counts an unlisted video, so only two items are left after paging through all of it
+        let listed = CachedPlaylist {
+            complete: true,
+            ..playlist.clone()
+        };
+        assert!(listed.covers(3));
+        assert!(listed.covers(10));
+        assert!(
+            CachedPlaylist {
+                items: vec![item("a"), item("b"), item("c")],
+                ..playlist
+            }
+            .covers(10)
+        );
+    }
+}
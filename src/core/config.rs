@@ -5,16 +5,53 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
-use crate::{core::utils::get_config_path, errors::TConfigError};
+use crate::{
+    core::{
+        api::DEFAULT_API_BASE,
+        cache::DEFAULT_TTL,
+        duration::Duration,
+        time::{BreakPolicy, DayBounds, MultiplierRange, Zone, parse_clock_time, parse_timezone},
+        utils::get_config_path,
+    },
+    errors::TConfigError,
+};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     api_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_multiplier: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_multiplier: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    day_start: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    day_end: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timezone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    breaks: Option<BreaksConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cache_ttl: Option<Duration>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_base: Option<String>,
     #[serde(skip)]
     path: PathBuf,
 }
 
+/// The `[breaks]` table: either `pomodoro = true`, or both `every` and `length`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BreaksConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pomodoro: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    every: Option<Duration>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    length: Option<Duration>,
+}
+
 impl Config {
     pub fn load() -> Result<Self, TConfigError> {
         match get_config_path() {
@@ -25,17 +62,49 @@ impl Config {
                     Ok(data) => match toml::from_str::<Self>(&data) {
                         Ok(mut cfg) => {
                             cfg.path = p;
+                            cfg.validate()?;
                             Ok(cfg)
                         }
                         Err(_) => Err(TConfigError::ParseFailed(p)),
                     },
-                    Err(e) => return Err(TConfigError::PathReadFailure(e.to_string())),
+                    Err(e) => Err(TConfigError::PathReadFailure(e.to_string())),
                 }
             }
-            Err(e) => return Err(TConfigError::NonexistentPath(e.to_string())),
+            Err(e) => Err(TConfigError::NonexistentPath(e.to_string())),
         }
     }
 
+    /// Loads the config file, falling back to the defaults if it does not exist yet.
+    pub fn load_or_default() -> Result<Self, TConfigError> {
+        let p = get_config_path().map_err(|e| TConfigError::NonexistentPath(e.to_string()))?;
+
+        if p.exists() {
+            Self::load()
+        } else {
+            Ok(Self {
+                path: p,
+                ..Default::default()
+            })
+        }
+    }
+
+    fn validate(&self) -> Result<(), TConfigError> {
+        let range = self.multiplier_range();
+
+        if range.min <= 0.0 || range.min > range.max {
+            return Err(TConfigError::InvalidValue(format!(
+                "multiplier range {}x..{}x must be positive and non-empty",
+                range.min, range.max
+            )));
+        }
+
+        self.day_bounds()?;
+        self.zone()?;
+        self.break_policy()?;
+
+        Ok(())
+    }
+
     pub fn update_write_key(&mut self, new_key: String) -> Result<(), TConfigError> {
         self.api_key = Some(new_key);
         self.save()?;
@@ -50,10 +119,83 @@ impl Config {
         Ok(())
     }
 
+    #[must_use]
     pub fn api_key(&self) -> Option<&str> {
         self.api_key.as_deref()
     }
 
+    /// The accepted multiplier range, with missing bounds taken from [`MultiplierRange::default`].
+    #[must_use]
+    pub fn multiplier_range(&self) -> MultiplierRange {
+        let default = MultiplierRange::default();
+
+        MultiplierRange {
+            min: self.min_multiplier.unwrap_or(default.min),
+            max: self.max_multiplier.unwrap_or(default.max),
+        }
+    }
+
+    /// The configured day boundaries, with a missing end taken from [`DayBounds::default`].
+    pub fn day_bounds(&self) -> Result<DayBounds, TConfigError> {
+        let parse =
+            |t: &str| parse_clock_time(t).map_err(|e| TConfigError::InvalidValue(e.to_string()));
+
+        Ok(DayBounds {
+            start: self.day_start.as_deref().map(parse).transpose()?,
+            end: match self.day_end.as_deref() {
+                Some(t) => parse(t)?,
+                None => DayBounds::default().end,
+            },
+        })
+    }
+
+    /// The configured timezone, defaulting to the system's local timezone.
+    pub fn zone(&self) -> Result<Zone, TConfigError> {
+        match self.timezone.as_deref() {
+            Some(name) => parse_timezone(name)
+                .map(Zone::Named)
+                .map_err(|e| TConfigError::InvalidValue(e.to_string())),
+            None => Ok(Zone::Local),
+        }
+    }
+
+    /// The configured breaks, if any.
+    pub fn break_policy(&self) -> Result<Option<BreakPolicy>, TConfigError> {
+        let Some(breaks) = &self.breaks else {
+            return Ok(None);
+        };
+
+        match (breaks.pomodoro, breaks.every, breaks.length) {
+            (Some(true), None, None) => Ok(Some(BreakPolicy::POMODORO)),
+            (Some(false) | None, Some(every), Some(length)) if !every.is_zero() => {
+                Ok(Some(BreakPolicy { every, length }))
+            }
+            (Some(false), None, None) => Ok(None),
+            _ => Err(TConfigError::InvalidValue(
+                "breaks need either `pomodoro = true` or a non-zero `every` and a `length`"
+                    .to_string(),
+            )),
+        }
+    }
+
+    /// How long cached API responses are used, [`DEFAULT_TTL`] unless configured.
+    #[must_use]
+    pub fn cache_ttl(&self) -> Duration {
+        self.cache_ttl.unwrap_or(DEFAULT_TTL)
+    }
+
+    /// The base URL of the YouTube Data API: `TRIMSEC_API_BASE` if set, then the config file, then
+    /// [`DEFAULT_API_BASE`].
+    #[must_use]
+    pub fn api_base(&self) -> String {
+        std::env::var("TRIMSEC_API_BASE")
+            .ok()
+            .filter(|b| !b.is_empty())
+            .or_else(|| self.api_base.clone())
+            .unwrap_or_else(|| DEFAULT_API_BASE.to_string())
+    }
+
+    #[must_use]
     pub fn path(&self) -> &Path {
         self.path.as_ref()
     }
@@ -0,0 +1,256 @@
+use std::{fmt::Display, iter::Sum, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{core::time::parse_duration, errors::TTimeError};
+
+/// A non-negative span of time with millisecond precision.
+///
+/// Unlike a bare amount of seconds, all arithmetic on this type is checked (or explicitly saturating), and it
+/// formats/parses using the same notation as the rest of trimsec (e.g. `1h2m3.5s`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Duration {
+    millis: u64,
+}
+
+impl Duration {
+    pub const ZERO: Duration = Duration { millis: 0 };
+
+    #[must_use]
+    pub const fn from_millis(millis: u64) -> Self {
+        Self { millis }
+    }
+
+    #[must_use]
+    pub const fn from_secs(secs: u64) -> Self {
+        Self {
+            millis: secs.saturating_mul(1000),
+        }
+    }
+
+    /// Creates a duration from fractional seconds, rounded to the nearest millisecond.
+    ///
+    /// Returns `None` if the value is negative, not finite, or too large to be represented.
+    #[must_use]
+    pub fn from_secs_f64(secs: f64) -> Option<Self> {
+        let millis = (secs * 1000.0).round();
+
+        if !millis.is_finite() || millis < 0.0 || millis >= u64::MAX as f64 {
+            None
+        } else {
+            Some(Self {
+                millis: millis as u64,
+            })
+        }
+    }
+
+    #[must_use]
+    pub const fn as_millis(&self) -> u64 {
+        self.millis
+    }
+
+    /// Returns the number of whole seconds, truncating any sub-second part.
+    #[must_use]
+    pub const fn as_secs(&self) -> u64 {
+        self.millis / 1000
+    }
+
+    #[must_use]
+    pub fn as_secs_f64(&self) -> f64 {
+        self.millis as f64 / 1000.0
+    }
+
+    #[must_use]
+    pub const fn is_zero(&self) -> bool {
+        self.millis == 0
+    }
+
+    #[must_use]
+    pub const fn checked_add(self, rhs: Self) -> Option<Self> {
+        match self.millis.checked_add(rhs.millis) {
+            Some(millis) => Some(Self { millis }),
+            None => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn checked_sub(self, rhs: Self) -> Option<Self> {
+        match self.millis.checked_sub(rhs.millis) {
+            Some(millis) => Some(Self { millis }),
+            None => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn saturating_add(self, rhs: Self) -> Self {
+        Self {
+            millis: self.millis.saturating_add(rhs.millis),
+        }
+    }
+
+    #[must_use]
+    pub const fn saturating_sub(self, rhs: Self) -> Self {
+        Self {
+            millis: self.millis.saturating_sub(rhs.millis),
+        }
+    }
+
+    /// Returns the absolute difference between two durations.
+    #[must_use]
+    pub const fn abs_diff(self, rhs: Self) -> Self {
+        Self {
+            millis: self.millis.abs_diff(rhs.millis),
+        }
+    }
+
+    #[must_use]
+    pub fn checked_mul_f64(self, rhs: f64) -> Option<Self> {
+        Self::from_secs_f64(self.as_secs_f64() * rhs)
+    }
+
+    /// Divides the duration by a factor, returning `None` for a zero (or otherwise invalid) divisor.
+    #[must_use]
+    pub fn checked_div_f64(self, rhs: f64) -> Option<Self> {
+        if rhs == 0.0 {
+            None
+        } else {
+            Self::from_secs_f64(self.as_secs_f64() / rhs)
+        }
+    }
+}
+
+impl From<std::time::Duration> for Duration {
+    fn from(value: std::time::Duration) -> Self {
+        Self {
+            millis: u64::try_from(value.as_millis()).unwrap_or(u64::MAX),
+        }
+    }
+}
+
+impl From<Duration> for std::time::Duration {
+    fn from(value: Duration) -> Self {
+        std::time::Duration::from_millis(value.millis)
+    }
+}
+
+impl Sum for Duration {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Duration::ZERO, Duration::saturating_add)
+    }
+}
+
+impl Display for Duration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0s");
+        }
+
+        let secs = self.as_secs();
+        let millis = self.millis % 1000;
+
+        for (value, unit) in [
+            (secs / 86400, "d"),
+            ((secs % 86400) / 3600, "h"),
+            ((secs % 3600) / 60, "m"),
+        ] {
+            if value != 0 {
+                write!(f, "{value}{unit}")?;
+            }
+        }
+
+        match (secs % 60, millis) {
+            (0, 0) => Ok(()),
+            (s, 0) => write!(f, "{s}s"),
+            (s, ms) => write!(f, "{s}.{}s", format!("{ms:03}").trim_end_matches('0')),
+        }
+    }
+}
+
+impl FromStr for Duration {
+    type Err = TTimeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_duration(s).map(|(duration, _)| duration)
+    }
+}
+
+impl Serialize for Duration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Duration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Duration::ZERO.to_string(), "0s");
+        assert_eq!(Duration::from_secs(90061).to_string(), "1d1h1m1s");
+        assert_eq!(Duration::from_secs(3600).to_string(), "1h");
+        assert_eq!(Duration::from_millis(3_723_500).to_string(), "1h2m3.5s");
+        assert_eq!(Duration::from_millis(250).to_string(), "0.25s");
+        assert_eq!(Duration::from_millis(60_005).to_string(), "1m0.005s");
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(
+            "1h2m3.5s".parse::<Duration>().unwrap(),
+            Duration::from_millis(3_723_500)
+        );
+        assert_eq!(
+            "01:02:03.500".parse::<Duration>().unwrap(),
+            Duration::from_millis(3_723_500)
+        );
+        assert!("1x".parse::<Duration>().is_err());
+
+        for millis in [0, 250, 60_005, 3_723_500, 90_061_000] {
+            let d = Duration::from_millis(millis);
+            assert_eq!(d.to_string().parse::<Duration>().unwrap(), d);
+        }
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let hour = Duration::from_secs(3600);
+        let minute = Duration::from_secs(60);
+
+        assert_eq!(hour.checked_add(minute), Some(Duration::from_secs(3660)));
+        assert_eq!(hour.checked_sub(minute), Some(Duration::from_secs(3540)));
+        assert_eq!(minute.checked_sub(hour), None);
+        assert_eq!(minute.saturating_sub(hour), Duration::ZERO);
+        assert_eq!(minute.abs_diff(hour), Duration::from_secs(3540));
+        assert_eq!(
+            Duration::from_millis(u64::MAX).checked_add(Duration::from_millis(1)),
+            None
+        );
+        assert_eq!(hour.checked_div_f64(2.0), Some(Duration::from_secs(1800)));
+        assert_eq!(hour.checked_div_f64(0.0), None);
+        assert_eq!(hour.checked_mul_f64(1.5), Some(Duration::from_secs(5400)));
+        assert_eq!(hour.checked_mul_f64(-1.0), None);
+        assert_eq!(
+            [hour, minute].into_iter().sum::<Duration>(),
+            Duration::from_secs(3660)
+        );
+    }
+
+    #[test]
+    fn test_serde() {
+        let d = Duration::from_millis(3_723_500);
+        let json = serde_json::to_string(&d).unwrap();
+
+        assert_eq!(json, "\"1h2m3.5s\"");
+        assert_eq!(serde_json::from_str::<Duration>(&json).unwrap(), d);
+        assert!(serde_json::from_str::<Duration>("\"nope\"").is_err());
+    }
+}
@@ -0,0 +1,583 @@
+//! Arithmetic expressions over durations, e.g. `12*45m`, `3h - 20m` or `(1h30m + 50m) * 2`.
+//!
+//! Expressions are tokenized into operators and literals first, where each literal is either a bare
+//! number or a duration understood by [`parse_literal`]. The tokens are then evaluated with a small
+//! recursive descent parser using the usual precedence rules.
+//!
+//! Any operand may be annotated with its own speed using `@`, e.g. `1h@1.25x + 30m@2x`. The result of an
+//! expression is therefore a list of [`Segment`]s rather than a single duration.
+//!
+//! Every token keeps the byte range it was read from, so that errors can point at the offending part of
+//! the expression (see [`Diagnostic`]).
+
+use std::ops::Range;
+
+use crate::{
+    core::{
+        duration::Duration,
+        time::{Segment, UNITS, parse_literal, parse_multiplier},
+    },
+    errors::{Diagnostic, TTimeError},
+};
+
+type Span = Range<usize>;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Duration(Duration),
+    Multiplier(f64),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    At,
+    LParen,
+    RParen,
+}
+
+/// A literal being read, with the position of each of its bytes in the expression.
+///
+/// Leading whitespace is left out of the literal, so the positions are needed to map errors from parsing the
+/// literal on its own back onto the expression.
+#[derive(Default)]
+struct Literal {
+    text: String,
+    offsets: Vec<usize>,
+    end: usize,
+}
+
+impl Literal {
+    fn push(&mut self, offset: usize, c: char) {
+        self.text.push(c);
+        self.offsets.extend(offset..offset + c.len_utf8());
+        self.end = offset + c.len_utf8();
+    }
+
+    /// Moves the diagnostic of an error from parsing this literal onto the whole expression.
+    fn relocate(&self, mut error: TTimeError, expression: &str) -> TTimeError {
+        if let Some(diagnostic) = error.diagnostic_mut() {
+            let position = |i: usize| self.offsets.get(i).copied().unwrap_or(self.end);
+            let start = position(diagnostic.span.start);
+            let end = if diagnostic.span.is_empty() {
+                start
+            } else {
+                position(diagnostic.span.end - 1) + 1
+            };
+
+            diagnostic.input = expression.to_string();
+            diagnostic.span = start..end;
+        }
+
+        error
+    }
+
+    /// Turns the literal read so far into a token, if there is one.
+    fn flush(
+        &mut self,
+        expression: &str,
+        tokens: &mut Vec<(Token, Span)>,
+    ) -> Result<(), TTimeError> {
+        let len = self.text.trim_end().len();
+        self.text.truncate(len);
+        self.offsets.truncate(len);
+
+        let (Some(&start), Some(&last)) = (self.offsets.first(), self.offsets.last()) else {
+            return Ok(());
+        };
+        self.end = last + 1;
+        let text = self.text.as_str();
+
+        let token = if tokens.last().is_some_and(|(t, _)| *t == Token::At) {
+            Token::Multiplier(parse_multiplier(text).map_err(|e| self.relocate(e, expression))?)
+        } else if text.chars().all(|c| c.is_ascii_digit() || c == '.') {
+            Token::Number(text.parse().map_err(|_| {
+                TTimeError::InvalidNumber(Diagnostic::new(expression, start..self.end))
+            })?)
+        } else {
+            Token::Duration(
+                Duration::from_secs_f64(
+                    parse_literal(text).map_err(|e| self.relocate(e, expression))?,
+                )
+                .ok_or(TTimeError::DurationOverflow)?,
+            )
+        };
+
+        tokens.push((token, start..self.end));
+        *self = Self::default();
+
+        Ok(())
+    }
+}
+
+/// Splits an expression into tokens. Whitespace is kept inside literals, where it separates their parts (as in
+/// `1h 30m` or `1 hour 30 minutes`).
+fn tokenize(expression: &str) -> Result<Vec<(Token, Span)>, TTimeError> {
+    let mut tokens = Vec::new();
+    let mut literal = Literal::default();
+
+    for (i, c) in expression.char_indices() {
+        let operator = match c {
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            '@' => Token::At,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            c if c.is_whitespace() && literal.text.is_empty() => continue,
+            c => {
+                literal.push(i, c);
+                continue;
+            }
+        };
+
+        literal.flush(expression, &mut tokens)?;
+        tokens.push((operator, i..i + 1));
+    }
+
+    literal.flush(expression, &mut tokens)?;
+    Ok(tokens)
+}
+
+/// The value of a (sub)expression.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Number(f64),
+    Segments(Vec<Segment>),
+}
+
+/// Scales every segment by a positive factor. Whole factors also scale the segment count, so that `12*45m`
+/// reads as twelve 45-minute segments.
+fn scale(segments: Vec<Segment>, factor: f64, divide: bool) -> Result<Value, TTimeError> {
+    segments
+        .into_iter()
+        .map(|s| {
+            let duration = if divide {
+                s.duration.checked_div_f64(factor)
+            } else {
+                s.duration.checked_mul_f64(factor)
+            }
+            .ok_or(TTimeError::DurationOverflow)?;
+
+            let count = if !divide && factor.fract() == 0.0 && factor >= 1.0 {
+                s.count.saturating_mul(factor as u64)
+            } else {
+                s.count
+            };
+
+            Ok(Segment {
+                duration,
+                count,
+                ..s
+            })
+        })
+        .collect::<Result<_, _>>()
+        .map(Value::Segments)
+}
+
+/// Takes `amount` away from the given segments, starting with the last one. Segments used up entirely are
+/// dropped, so that they neither count as durations nor show up in a breakdown.
+///
+/// Returns `None` if the segments are shorter than `amount`.
+fn subtract(mut segments: Vec<Segment>, amount: Duration) -> Option<Value> {
+    let mut remaining = amount;
+
+    for segment in segments.iter_mut().rev() {
+        let taken = remaining.min(segment.duration);
+        segment.duration = segment.duration.saturating_sub(taken);
+        remaining = remaining.saturating_sub(taken);
+    }
+
+    segments.retain(|s| !s.duration.is_zero());
+    remaining.is_zero().then_some(Value::Segments(segments))
+}
+
+struct Parser<'a> {
+    expression: &'a str,
+    tokens: Vec<(Token, Span)>,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn advance(&mut self) -> Option<(Token, Span)> {
+        self.position += 1;
+        self.tokens.get(self.position - 1).cloned()
+    }
+
+    /// The span of the next token, or the (empty) end of the expression if there is none.
+    fn next_span(&self) -> Span {
+        self.tokens.get(self.position).map_or_else(
+            || {
+                let end = self.expression.trim_end().len();
+                end..end
+            },
+            |(_, span)| span.clone(),
+        )
+    }
+
+    fn diagnostic(&self, span: Span, expected: &[&str]) -> Diagnostic {
+        Diagnostic::new(self.expression, span).with_expected(expected)
+    }
+
+    /// expression := term (('+' | '-') term)*
+    fn expression(&mut self) -> Result<(Value, Span), TTimeError> {
+        let (mut value, mut span) = self.term()?;
+
+        while let Some(op) = self.peek() {
+            let subtract_rhs = match op {
+                Token::Plus => false,
+                Token::Minus => true,
+                _ => break,
+            };
+            self.advance();
+            let (rhs, rhs_span) = self.term()?;
+            let full_span = span.start..rhs_span.end;
+
+            value = match (value, rhs) {
+                (Value::Number(a), Value::Number(b)) => {
+                    Value::Number(if subtract_rhs { a - b } else { a + b })
+                }
+                (Value::Segments(lhs), Value::Segments(rhs)) if subtract_rhs => {
+                    // a speed on the subtracted part has no meaning
+                    if rhs.iter().any(|s| s.multiplier.is_some()) {
+                        return Err(TTimeError::InvalidExpression(
+                            self.diagnostic(rhs_span, &["a duration without `@`"]),
+                        ));
+                    }
+                    subtract(lhs, rhs.iter().map(|s| s.duration).sum()).ok_or_else(|| {
+                        TTimeError::NegativeDuration(self.diagnostic(full_span.clone(), &[]))
+                    })?
+                }
+                (Value::Segments(mut lhs), Value::Segments(rhs)) => {
+                    lhs.extend(rhs);
+                    Value::Segments(lhs)
+                }
+                (Value::Number(_), _) => {
+                    return Err(TTimeError::InvalidExpression(
+                        self.diagnostic(rhs_span, &["a number"]),
+                    ));
+                }
+                (Value::Segments(_), _) => {
+                    return Err(TTimeError::InvalidExpression(
+                        self.diagnostic(rhs_span, &["a duration"]),
+                    ));
+                }
+            };
+            span = full_span;
+        }
+
+        Ok((value, span))
+    }
+
+    /// term := annotated (('*' | '/') annotated)*
+    fn term(&mut self) -> Result<(Value, Span), TTimeError> {
+        let (mut value, mut span) = self.annotated()?;
+
+        while let Some(op) = self.peek() {
+            let divide = match op {
+                Token::Star => false,
+                Token::Slash => true,
+                _ => break,
+            };
+            self.advance();
+            let (rhs, rhs_span) = self.annotated()?;
+
+            value = match (value, rhs) {
+                (_, Value::Number(b)) if divide && b == 0.0 => {
+                    return Err(TTimeError::InvalidExpression(
+                        self.diagnostic(rhs_span, &["a non-zero number"]),
+                    ));
+                }
+                (Value::Number(a), Value::Number(b)) => {
+                    Value::Number(if divide { a / b } else { a * b })
+                }
+                (Value::Number(n), Value::Segments(_)) if !divide && n < 0.0 => {
+                    return Err(TTimeError::NegativeDuration(self.diagnostic(span, &[])));
+                }
+                (Value::Segments(_), Value::Number(n)) if n < 0.0 => {
+                    return Err(TTimeError::NegativeDuration(self.diagnostic(rhs_span, &[])));
+                }
+                (Value::Number(n), Value::Segments(segments)) if !divide => {
+                    scale(segments, n, false)?
+                }
+                (Value::Segments(segments), Value::Number(n)) => scale(segments, n, divide)?,
+                _ => {
+                    return Err(TTimeError::InvalidExpression(
+                        self.diagnostic(rhs_span, &["a number"]),
+                    ));
+                }
+            };
+            span = span.start..rhs_span.end;
+        }
+
+        Ok((value, span))
+    }
+
+    /// annotated := factor ('@' multiplier)?
+    ///
+    /// The annotation only applies to segments without a speed of their own, so in `(1h@2x + 30m)@1.5x`
+    /// the first hour stays at 2x.
+    fn annotated(&mut self) -> Result<(Value, Span), TTimeError> {
+        let (value, span) = self.factor()?;
+
+        if self.peek() != Some(&Token::At) {
+            return Ok((value, span));
+        }
+        self.advance();
+
+        let Value::Segments(segments) = value else {
+            return Err(TTimeError::InvalidExpression(
+                self.diagnostic(span, &["a duration"]),
+            ));
+        };
+
+        let multiplier_span = self.next_span();
+        match self.advance() {
+            Some((Token::Multiplier(m), end)) => Ok((
+                Value::Segments(
+                    segments
+                        .into_iter()
+                        .map(|s| Segment {
+                            multiplier: s.multiplier.or(Some(m)),
+                            ..s
+                        })
+                        .collect(),
+                ),
+                span.start..end.end,
+            )),
+            _ => Err(TTimeError::InvalidMultiplierFormat(
+                self.diagnostic(multiplier_span, &["a multiplier (e.g. `1.25x`)"]),
+            )),
+        }
+    }
+
+    /// factor := number | duration | '(' expression ')'
+    fn factor(&mut self) -> Result<(Value, Span), TTimeError> {
+        let span = self.next_span();
+
+        match self.advance() {
+            Some((Token::Number(n), span)) => Ok((Value::Number(n), span)),
+            Some((Token::Duration(duration), span)) => Ok((
+                Value::Segments(vec![Segment {
+                    duration,
+                    multiplier: None,
+                    count: 1,
+                }]),
+                span,
+            )),
+            Some((Token::LParen, open)) => {
+                let (value, _) = self.expression()?;
+                let close = self.next_span();
+
+                match self.advance() {
+                    Some((Token::RParen, close)) => Ok((value, open.start..close.end)),
+                    _ => Err(TTimeError::InvalidExpression(
+                        self.diagnostic(close, &["an operator", "`)`"]),
+                    )),
+                }
+            }
+            _ => Err(TTimeError::InvalidExpression(
+                self.diagnostic(span, &["a duration", "a number", "`(`"]),
+            )),
+        }
+    }
+}
+
+/// Evaluates a duration expression into the segments it describes.
+pub(crate) fn evaluate(expression: &str) -> Result<Vec<Segment>, TTimeError> {
+    let tokens = tokenize(expression)?;
+
+    if tokens.is_empty() {
+        return Err(TTimeError::InvalidDurationFormat(
+            Diagnostic::new(expression, 0..0).with_expected(["a duration"]),
+        ));
+    }
+
+    let mut parser = Parser {
+        expression,
+        tokens,
+        position: 0,
+    };
+    let (value, span) = parser.expression()?;
+
+    if parser.peek().is_some() {
+        return Err(TTimeError::InvalidExpression(parser.diagnostic(
+            parser.next_span(),
+            &["an operator", "the end of the duration"],
+        )));
+    }
+
+    match value {
+        Value::Segments(segments) => Ok(segments),
+        // a bare number is most likely a duration with its unit left out
+        Value::Number(_) => Err(TTimeError::InvalidDurationFormat(
+            Diagnostic::new(expression, span.end..span.end).with_expected(UNITS),
+        )),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("(1h 30m + 50m) * 2").unwrap(),
+            vec![
+                (Token::LParen, 0..1),
+                (Token::Duration(Duration::from_secs(5400)), 1..7),
+                (Token::Plus, 8..9),
+                (Token::Duration(Duration::from_secs(3000)), 10..13),
+                (Token::RParen, 13..14),
+                (Token::Star, 15..16),
+                (Token::Number(2.0), 17..18),
+            ]
+        );
+        assert_eq!(
+            tokenize("1h@1.25x").unwrap(),
+            vec![
+                (Token::Duration(Duration::from_secs(3600)), 0..2),
+                (Token::At, 2..3),
+                (Token::Multiplier(1.25), 3..8),
+            ]
+        );
+        assert!(tokenize("1x").is_err());
+    }
+
+    fn totals(expression: &str) -> (Duration, u64) {
+        let segments = evaluate(expression).unwrap();
+        (
+            segments.iter().map(|s| s.duration).sum(),
+            segments.iter().map(|s| s.count).sum(),
+        )
+    }
+
+    fn segment(secs: u64, multiplier: Option<f64>, count: u64) -> Segment {
+        Segment {
+            duration: Duration::from_secs(secs),
+            multiplier,
+            count,
+        }
+    }
+
+    #[test]
+    fn test_evaluate() {
+        assert_eq!(totals("12*45m"), (Duration::from_secs(32400), 12));
+        assert_eq!(totals("45m * 12"), (Duration::from_secs(32400), 12));
+        assert_eq!(totals("3h - 20m"), (Duration::from_secs(9600), 1));
+        assert_eq!(totals("(1h30m + 50m) * 2"), (Duration::from_secs(16800), 4));
+        assert_eq!(totals("1h + 2 * 30m"), (Duration::from_secs(7200), 3));
+        assert_eq!(totals("(2 + 3) * 10m"), (Duration::from_secs(3000), 5));
+        assert_eq!(totals("1.5 * 1h"), (Duration::from_secs(5400), 1));
+        assert_eq!(totals("3h / 2"), (Duration::from_secs(5400), 1));
+        assert_eq!(totals("1:00:00 - PT15M"), (Duration::from_secs(2700), 1));
+        assert_eq!(totals("2*30m - 30m"), (Duration::from_secs(1800), 2));
+        assert_eq!(totals("1h + 30m - 30m"), (Duration::from_secs(3600), 1));
+        assert_eq!(totals("1h - 1h"), (Duration::ZERO, 0));
+    }
+
+    #[test]
+    fn test_evaluate_annotations() {
+        assert_eq!(
+            evaluate("1h@1.25x+30m@2x+45m").unwrap(),
+            vec![
+                segment(3600, Some(1.25), 1),
+                segment(1800, Some(2.0), 1),
+                segment(2700, None, 1),
+            ]
+        );
+        assert_eq!(
+            evaluate("(1h@2x + 30m)@1.5x").unwrap(),
+            vec![segment(3600, Some(2.0), 1), segment(1800, Some(1.5), 1)]
+        );
+        assert_eq!(
+            evaluate("12*45m@2").unwrap(),
+            vec![segment(32400, Some(2.0), 12)]
+        );
+        assert_eq!(
+            evaluate("1h@2x + 1h - 1h30m").unwrap(),
+            vec![segment(1800, Some(2.0), 1)]
+        );
+        assert!(evaluate("1h - 30m@2x").is_err());
+        assert!(evaluate("2@2x * 1h").is_err());
+        assert!(evaluate("1h@").is_err());
+        assert!(evaluate("1h@abc").is_err());
+    }
+
+    #[test]
+    fn test_evaluate_errors() {
+        assert!(evaluate("").is_err());
+        assert!(evaluate("12").is_err());
+        assert!(evaluate("20m - 1h").is_err());
+        assert!(evaluate("1h * 1h").is_err());
+        assert!(evaluate("1h + 2").is_err());
+        assert!(evaluate("(1h + 2m").is_err());
+        assert!(evaluate("1h + 2m)").is_err());
+        assert!(evaluate("1h / 0").is_err());
+        assert!(evaluate("1h +").is_err());
+        assert!(evaluate("* 1h").is_err());
+    }
+
+    fn diagnostic(expression: &str) -> Diagnostic {
+        evaluate(expression)
+            .unwrap_err()
+            .diagnostic()
+            .cloned()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let d = diagnostic("1h3o m");
+        assert_eq!((d.snippet(), d.span.clone()), ("o", 3..4));
+        assert_eq!(
+            d.to_string(),
+            "\n  1h3o m\n     ^ expected `s`, `m`, `h` or `d`"
+        );
+
+        let d = diagnostic("1 hour 30 minuts");
+        assert_eq!(d.snippet(), "minuts");
+        assert_eq!(d.suggested_input().unwrap(), "1 hour 30 minutes");
+
+        let d = diagnostic("2H + 5 sec");
+        assert_eq!(d.snippet(), "H");
+        assert_eq!(d.suggested_input().unwrap(), "2h + 5 sec");
+
+        assert_eq!(diagnostic("1mn").suggested_input().unwrap(), "1min");
+        assert_eq!(diagnostic("1hx").suggested_input().unwrap(), "1hr");
+        assert_eq!(diagnostic("1hqz").snippet(), "q");
+        assert_eq!(diagnostic("1h and").span, 6..6);
+        assert_eq!(diagnostic("1h, and 5m").snippet(), "and");
+        assert_eq!(diagnostic("1.2.3m").snippet(), "1.2.3");
+        assert_eq!(diagnostic("1h30").span, 4..4);
+        assert_eq!(diagnostic("1:75").snippet(), "75");
+        assert_eq!(diagnostic("PT1S2H").snippet(), "H");
+        assert_eq!(diagnostic("1h + 2").snippet(), "2");
+        assert_eq!(diagnostic("1h + 2m)").snippet(), ")");
+        assert_eq!(diagnostic("20m - 1h").snippet(), "20m - 1h");
+        assert_eq!(diagnostic("1h@fast").snippet(), "fast");
+        assert_eq!(diagnostic("(1h + ").span, 5..5);
+        assert_eq!(diagnostic("(2-3)*10m").snippet(), "(2-3)");
+        assert_eq!(diagnostic("10m / (1 - 2)").snippet(), "(1 - 2)");
+
+        assert!(matches!(
+            evaluate("1.2.3"),
+            Err(TTimeError::InvalidNumber(_))
+        ));
+        assert!(matches!(
+            evaluate("20m - 1h"),
+            Err(TTimeError::NegativeDuration(_))
+        ));
+        assert!(matches!(
+            evaluate("(2-3)*10m"),
+            Err(TTimeError::NegativeDuration(_))
+        ));
+    }
+}
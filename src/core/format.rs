@@ -0,0 +1,272 @@
+//! Rendering durations for output, e.g. `1h23m30s`, `83.5 min` or `1 hour, 23 minutes, 30 seconds`.
+
+use clap::ValueEnum;
+
+use crate::core::duration::Duration;
+
+/// A single unit to express a whole duration in (e.g. `83.5 min` rather than `1h23m30s`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TimeUnit {
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+}
+
+impl TimeUnit {
+    const fn millis(self) -> u64 {
+        match self {
+            Self::Seconds => 1000,
+            Self::Minutes => 60 * 1000,
+            Self::Hours => 3600 * 1000,
+            Self::Days => 86400 * 1000,
+        }
+    }
+
+    /// The designator used in compact notation, e.g. the `m` in `1h23m`.
+    const fn symbol(self) -> &'static str {
+        match self {
+            Self::Seconds => "s",
+            Self::Minutes => "m",
+            Self::Hours => "h",
+            Self::Days => "d",
+        }
+    }
+
+    /// The abbreviated and the long (singular) name of the unit.
+    const fn names(self) -> (&'static str, &'static str) {
+        match self {
+            Self::Seconds => ("s", "second"),
+            Self::Minutes => ("min", "minute"),
+            Self::Hours => ("h", "hour"),
+            Self::Days => ("d", "day"),
+        }
+    }
+}
+
+/// How to round a duration to the precision it is displayed with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Rounding {
+    /// Round down, dropping anything below the displayed precision.
+    #[default]
+    Floor,
+    /// Round to the nearest value, with halves rounded up.
+    Nearest,
+    /// Round up.
+    Ceil,
+}
+
+impl Rounding {
+    /// Divides `value` by `divisor`, rounding the quotient.
+    const fn divide(self, value: u128, divisor: u128) -> u128 {
+        match self {
+            Self::Floor => value / divisor,
+            Self::Nearest => (value + divisor / 2) / divisor,
+            Self::Ceil => value.div_ceil(divisor),
+        }
+    }
+}
+
+/// Options for displaying durations.
+///
+/// The default renders the compact notation trimsec has always used (`1d1h1m1s`), truncated to whole seconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimeFormat {
+    /// Expresses the whole duration in a single unit, with up to two decimals.
+    pub unit: Option<TimeUnit>,
+    /// Rounding applied at the last displayed digit.
+    pub rounding: Rounding,
+    /// Shows seconds with millisecond precision (e.g. `1m2.5s`), which matters for short clips.
+    pub subseconds: bool,
+    /// Spells out the units, e.g. `1 hour, 23 minutes` instead of `1h23m`.
+    pub long: bool,
+}
+
+impl TimeFormat {
+    #[must_use]
+    pub fn format(&self, duration: Duration) -> String {
+        match self.unit {
+            Some(unit) => self.format_in(duration, unit),
+            None => self.format_split(duration),
+        }
+    }
+
+    /// Formats the duration as a decimal amount of a single unit, e.g. `83.5 min`.
+    fn format_in(&self, duration: Duration, unit: TimeUnit) -> String {
+        let hundredths = self.rounding.divide(
+            u128::from(duration.as_millis()) * 100,
+            u128::from(unit.millis()),
+        );
+        let value = decimal(hundredths, 2);
+        let (short, long) = unit.names();
+
+        if !self.long {
+            format!("{value} {short}")
+        } else if hundredths == 100 {
+            format!("{value} {long}")
+        } else {
+            format!("{value} {long}s")
+        }
+    }
+
+    /// Formats the duration split into days, hours, minutes and seconds, e.g. `1h23m30s`.
+    fn format_split(&self, duration: Duration) -> String {
+        // everything is counted in the smallest displayed unit: milliseconds or whole seconds
+        let (total, per_second) = if self.subseconds {
+            (u128::from(duration.as_millis()), 1000)
+        } else {
+            (
+                self.rounding.divide(u128::from(duration.as_millis()), 1000),
+                1,
+            )
+        };
+
+        let seconds = total / per_second;
+        let parts = [
+            (seconds / 86400, TimeUnit::Days),
+            ((seconds % 86400) / 3600, TimeUnit::Hours),
+            ((seconds % 3600) / 60, TimeUnit::Minutes),
+        ]
+        .into_iter()
+        .filter(|(value, _)| *value != 0)
+        .map(|(value, unit)| (value.to_string(), value == 1, unit))
+        .chain(
+            Some(total % (60 * per_second))
+                .filter(|s| *s != 0 || total == 0)
+                .map(|s| {
+                    let digits = if self.subseconds { 3 } else { 0 };
+                    (decimal(s, digits), s == per_second, TimeUnit::Seconds)
+                }),
+        );
+
+        let parts: Vec<String> = parts
+            .map(|(value, singular, unit)| {
+                let (_, long) = unit.names();
+
+                match (self.long, singular) {
+                    (false, _) => format!("{value}{}", unit.symbol()),
+                    (true, true) => format!("{value} {long}"),
+                    (true, false) => format!("{value} {long}s"),
+                }
+            })
+            .collect();
+
+        parts.join(if self.long { ", " } else { "" })
+    }
+}
+
+/// Renders a fixed-point value with the given number of decimal digits, dropping trailing zeros.
+fn decimal(value: u128, digits: u32) -> String {
+    let scale = 10u128.pow(digits);
+    let (whole, fraction) = (value / scale, value % scale);
+
+    if fraction == 0 {
+        whole.to_string()
+    } else {
+        let fraction = format!("{fraction:0width$}", width = digits as usize);
+        format!("{whole}.{}", fraction.trim_end_matches('0'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_compact() {
+        let format = TimeFormat::default();
+
+        assert_eq!(format.format(Duration::from_secs(90061)), "1d1h1m1s");
+        assert_eq!(format.format(Duration::from_secs(3600)), "1h");
+        assert_eq!(format.format(millis(5_999)), "5s");
+        assert_eq!(format.format(Duration::ZERO), "0s");
+        assert_eq!(format.format(millis(400)), "0s");
+    }
+
+    #[test]
+    fn test_rounding() {
+        let nearest = TimeFormat {
+            rounding: Rounding::Nearest,
+            ..Default::default()
+        };
+        let ceil = TimeFormat {
+            rounding: Rounding::Ceil,
+            ..Default::default()
+        };
+
+        assert_eq!(nearest.format(millis(5_499)), "5s");
+        assert_eq!(nearest.format(millis(59_500)), "1m");
+        assert_eq!(ceil.format(millis(60_001)), "1m1s");
+        assert_eq!(ceil.format(Duration::from_secs(60)), "1m");
+    }
+
+    #[test]
+    fn test_subseconds() {
+        let format = TimeFormat {
+            subseconds: true,
+            ..Default::default()
+        };
+
+        assert_eq!(format.format(millis(62_500)), "1m2.5s");
+        assert_eq!(format.format(millis(250)), "0.25s");
+        assert_eq!(format.format(Duration::from_secs(3600)), "1h");
+    }
+
+    #[test]
+    fn test_unit() {
+        let minutes = TimeFormat {
+            unit: Some(TimeUnit::Minutes),
+            ..Default::default()
+        };
+
+        assert_eq!(minutes.format(Duration::from_secs(5010)), "83.5 min");
+        assert_eq!(minutes.format(Duration::from_secs(20)), "0.33 min");
+        assert_eq!(
+            TimeFormat {
+                rounding: Rounding::Ceil,
+                ..minutes
+            }
+            .format(Duration::from_secs(20)),
+            "0.34 min"
+        );
+        assert_eq!(
+            TimeFormat {
+                unit: Some(TimeUnit::Hours),
+                long: true,
+                ..Default::default()
+            }
+            .format(Duration::from_secs(3600)),
+            "1 hour"
+        );
+    }
+
+    #[test]
+    fn test_long() {
+        let format = TimeFormat {
+            long: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            format.format(Duration::from_secs(4980)),
+            "1 hour, 23 minutes"
+        );
+        assert_eq!(
+            format.format(Duration::from_secs(90061)),
+            "1 day, 1 hour, 1 minute, 1 second"
+        );
+        assert_eq!(format.format(Duration::ZERO), "0 seconds");
+        assert_eq!(
+            TimeFormat {
+                subseconds: true,
+                ..format
+            }
+            .format(millis(61_500)),
+            "1 minute, 1.5 seconds"
+        );
+    }
+}
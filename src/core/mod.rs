@@ -1,5 +1,11 @@
 pub mod api;
+pub mod cache;
 pub mod config;
+pub mod duration;
+pub mod expr;
+pub mod format;
+pub mod plan;
+pub mod sources;
 pub mod style;
 pub mod time;
 pub mod utils;
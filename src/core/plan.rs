@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, NaiveDate, TimeDelta, Weekday};
+
+use crate::{
+    core::{
+        duration::Duration,
+        time::{BreakPolicy, parse_duration},
+    },
+    errors::TTimeError,
+};
+
+/// A single thing to watch, with its duration already trimmed by the multiplier.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanItem {
+    pub title: String,
+    pub duration: Duration,
+}
+
+/// The amount of time available on each day of the week.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WeeklyBudget {
+    /// The budget for days without an override.
+    pub default: Duration,
+    pub overrides: HashMap<Weekday, Duration>,
+}
+
+impl WeeklyBudget {
+    #[must_use]
+    pub fn for_day(&self, weekday: Weekday) -> Duration {
+        self.overrides
+            .get(&weekday)
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    /// Adds an override from a `day=duration` string, e.g. `sat=5h` or `monday=1h30m`.
+    pub fn add_override(&mut self, spec: &str) -> Result<(), TTimeError> {
+        let Some((day, duration)) = spec.split_once('=') else {
+            return Err(TTimeError::InvalidWeekdayBudget(spec.to_string()));
+        };
+
+        let weekday: Weekday = day
+            .trim()
+            .parse()
+            .map_err(|_| TTimeError::InvalidWeekdayBudget(spec.to_string()))?;
+        let (duration, _) = parse_duration(duration)?;
+
+        self.overrides.insert(weekday, duration);
+        Ok(())
+    }
+}
+
+/// The items scheduled for a single day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanDay {
+    pub date: NaiveDate,
+    pub budget: Duration,
+    pub items: Vec<PlanItem>,
+    /// The time the day's items take including breaks, which is what has to fit the budget.
+    pub session: Duration,
+}
+
+impl PlanDay {
+    /// The watch time of the day's items, without breaks.
+    #[must_use]
+    pub fn total(&self) -> Duration {
+        self.items.iter().map(|i| i.duration).sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    /// Days with at least one item, in order.
+    pub days: Vec<PlanDay>,
+    /// Items that could not be scheduled before the deadline.
+    pub unscheduled: Vec<PlanItem>,
+}
+
+/// Distributes items over the days from `start` up to and including `deadline`, keeping their order. With
+/// breaks, each day's session including them has to fit the day's budget.
+///
+/// Items are never split across days. An item longer than a whole day's budget is given a day of its own
+/// rather than blocking the rest of the schedule, as long as the day's budget isn't capped: the first day's
+/// budget is capped by `first_day_left`, as part of it may already be over.
+#[must_use]
+pub fn schedule(
+    items: Vec<PlanItem>,
+    budget: &WeeklyBudget,
+    start: NaiveDate,
+    deadline: NaiveDate,
+    first_day_left: Duration,
+    breaks: Option<BreakPolicy>,
+) -> Schedule {
+    let session = |watch: Duration| breaks.map_or(watch, |b| b.session_length(watch));
+
+    let mut pending = items.into_iter().peekable();
+    let mut days = Vec::new();
+    let mut date = start;
+
+    while date <= deadline && pending.peek().is_some() {
+        let full_budget = budget.for_day(date.weekday());
+        let mut day_budget = full_budget;
+        if date == start {
+            day_budget = day_budget.min(first_day_left);
+        }
+
+        let mut day = PlanDay {
+            date,
+            budget: day_budget,
+            items: Vec::new(),
+            session: Duration::ZERO,
+        };
+        let mut watch = Duration::ZERO;
+
+        while let Some(item) = pending.peek() {
+            let fits = session(watch.saturating_add(item.duration)) <= day_budget;
+            let oversized = day.items.is_empty()
+                && day_budget == full_budget
+                && session(item.duration) > full_budget;
+
+            if !(fits || (oversized && !day_budget.is_zero())) {
+                break;
+            }
+
+            watch = watch.saturating_add(item.duration);
+            day.items.extend(pending.next());
+        }
+        day.session = session(watch);
+
+        if !day.items.is_empty() {
+            days.push(day);
+        }
+
+        date += TimeDelta::days(1);
+    }
+
+    Schedule {
+        days,
+        unscheduled: pending.collect(),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn items(minutes: &[u64]) -> Vec<PlanItem> {
+        minutes
+            .iter()
+            .enumerate()
+            .map(|(i, m)| PlanItem {
+                title: format!("Item {}", i + 1),
+                duration: Duration::from_secs(m * 60),
+            })
+            .collect()
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_weekly_budget() {
+        let mut budget = WeeklyBudget {
+            default: Duration::from_secs(3600),
+            ..Default::default()
+        };
+        budget.add_override("sat=5h").unwrap();
+        budget.add_override("Sunday=0s").unwrap();
+
+        assert_eq!(budget.for_day(Weekday::Mon), Duration::from_secs(3600));
+        assert_eq!(budget.for_day(Weekday::Sat), Duration::from_secs(5 * 3600));
+        assert_eq!(budget.for_day(Weekday::Sun), Duration::ZERO);
+        assert!(budget.add_override("sat").is_err());
+        assert!(budget.add_override("someday=1h").is_err());
+        assert!(budget.add_override("sat=1x").is_err());
+    }
+
+    #[test]
+    fn test_schedule() {
+        let budget = WeeklyBudget {
+            default: Duration::from_secs(3600),
+            ..Default::default()
+        };
+
+        // 2026-10-19 is a Monday
+        let plan = schedule(
+            items(&[30, 30, 45, 20, 50]),
+            &budget,
+            date("2026-10-19"),
+            date("2026-10-25"),
+            Duration::from_secs(45 * 60),
+            None,
+        );
+
+        let titles: Vec<Vec<&str>> = plan
+            .days
+            .iter()
+            .map(|d| d.items.iter().map(|i| i.title.as_str()).collect())
+            .collect();
+
+        assert_eq!(
+            titles,
+            vec![
+                vec!["Item 1"],
+                vec!["Item 2"],
+                vec!["Item 3"],
+                vec!["Item 4"],
+                vec!["Item 5"],
+            ]
+        );
+        assert_eq!(plan.days[0].budget, Duration::from_secs(45 * 60));
+        assert_eq!(plan.days[4].date, date("2026-10-23"));
+        assert!(plan.unscheduled.is_empty());
+
+        // an item longer than a day waits for a full day, rather than taking what's left of today
+        let plan = schedule(
+            items(&[120]),
+            &budget,
+            date("2026-10-19"),
+            date("2026-10-25"),
+            Duration::from_secs(10 * 60),
+            None,
+        );
+        assert_eq!(plan.days.len(), 1);
+        assert_eq!(plan.days[0].date, date("2026-10-20"));
+        assert_eq!(plan.days[0].budget, Duration::from_secs(3600));
+    }
+
+    #[test]
+    fn test_schedule_deadline_and_overrides() {
+        let mut budget = WeeklyBudget::default();
+        budget.add_override("tue=2h").unwrap();
+
+        let plan = schedule(
+            items(&[60, 60, 150, 10]),
+            &budget,
+            date("2026-10-19"),
+            date("2026-10-27"),
+            Duration::from_secs(86400),
+            None,
+        );
+
+        // only Tuesdays have a budget; the 150-minute item gets a Tuesday of its own
+        assert_eq!(plan.days.len(), 2);
+        assert_eq!(plan.days[0].date, date("2026-10-20"));
+        assert_eq!(plan.days[0].total(), Duration::from_secs(2 * 3600));
+        assert_eq!(plan.days[1].date, date("2026-10-27"));
+        assert_eq!(plan.days[1].items.len(), 1);
+        assert_eq!(plan.unscheduled, items(&[60, 60, 150, 10])[3..].to_vec());
+    }
+
+    #[test]
+    fn test_schedule_breaks() {
+        let budget = WeeklyBudget {
+            default: Duration::from_secs(3600),
+            ..Default::default()
+        };
+        let plan = |breaks| {
+            schedule(
+                items(&[20, 20, 20, 90]),
+                &budget,
+                date("2026-10-19"),
+                date("2026-10-25"),
+                Duration::from_secs(86400),
+                breaks,
+            )
+        };
+
+        let without = plan(None);
+        assert_eq!(without.days[0].items.len(), 3);
+        assert_eq!(without.days[0].session, Duration::from_secs(3600));
+
+        // three items would take 1h10m with pomodoro breaks, so only two fit the hour
+        let with = plan(Some(BreakPolicy::POMODORO));
+        assert_eq!(with.days[0].items.len(), 2);
+        assert_eq!(with.days[0].total(), Duration::from_secs(40 * 60));
+        assert_eq!(with.days[0].session, Duration::from_secs(45 * 60));
+
+        // 90 minutes are 1h45m with breaks, so they get a day of their own
+        assert_eq!(with.days[2].items, items(&[20, 20, 20, 90])[3..].to_vec());
+        assert_eq!(with.days[2].session, Duration::from_secs(105 * 60));
+        assert!(with.unscheduled.is_empty());
+    }
+}
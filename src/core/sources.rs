@@ -0,0 +1,189 @@
+//! Adding up several sources, e.g. two playlists, a standalone video and `45m` of reading.
+
+use std::collections::HashSet;
+
+use crate::{
+    core::{
+        api::ApiClientManager,
+        duration::Duration,
+        time::parse_duration,
+        youtils::{ItemRange, YoutubeId, get_youtube_id},
+    },
+    errors::TYoutubeError,
+};
+
+/// Something to count: a YouTube link or a plain duration.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    Youtube(YoutubeId),
+    /// A duration expression, with the number of durations it stands for (e.g. 12 for `12*45m`).
+    Duration((Duration, u64)),
+}
+
+impl Source {
+    /// Parses a source, trying a duration first, since a short one could also pass for a bare video ID.
+    #[must_use]
+    pub fn parse(input: &str) -> Option<Self> {
+        match parse_duration(input) {
+            Ok(duration) => Some(Self::Duration(duration)),
+            Err(_) => get_youtube_id(input).map(Self::Youtube),
+        }
+    }
+}
+
+/// The part of a combined total that a single source accounts for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subtotal {
+    /// The source as given by the user.
+    pub label: String,
+    pub duration: Duration,
+    /// The number of videos or durations counted.
+    pub items: usize,
+    /// The number of videos skipped, since an earlier source already counted them.
+    pub duplicates: usize,
+    /// The position a video link starts at, which is left out of the duration.
+    pub start: Option<Duration>,
+}
+
+/// Adds up sources one by one, counting every video only once.
+#[derive(Debug, Default)]
+pub struct Tally {
+    pub subtotals: Vec<Subtotal>,
+    seen: HashSet<String>,
+}
+
+impl Tally {
+    #[must_use]
+    pub fn total(&self) -> Duration {
+        self.subtotals.iter().map(|s| s.duration).sum()
+    }
+
+    /// The number of videos and durations counted across all sources.
+    #[must_use]
+    pub fn items(&self) -> usize {
+        self.subtotals.iter().map(|s| s.items).sum()
+    }
+
+    /// The number of videos skipped across all sources, since they were already counted.
+    #[must_use]
+    pub fn duplicates(&self) -> usize {
+        self.subtotals.iter().map(|s| s.duplicates).sum()
+    }
+
+    pub fn add_duration(&mut self, label: &str, (duration, splits): (Duration, u64)) {
+        self.subtotals.push(Subtotal {
+            label: label.to_string(),
+            duration,
+            items: splits as usize,
+            duplicates: 0,
+            start: None,
+        });
+    }
+
+    /// Fetches the videos of a YouTube link (limited to `items` for playlists and channels) and adds those
+    /// that haven't been counted yet.
+    pub fn add_youtube(
+        &mut self,
+        label: &str,
+        manager: &ApiClientManager,
+        id: &YoutubeId,
+        items: ItemRange,
+    ) -> Result<(), TYoutubeError> {
+        let (ids, duplicates) = self.claim(manager.expand_id(id, items)?);
+        let videos = manager.fetch_video_items(&ids)?;
+
+        // a duplicate video was counted in full before, so its timestamp no longer applies
+        let start = id.start().filter(|_| !ids.is_empty());
+        let duration = videos
+            .iter()
+            .map(|v| {
+                v.content_details
+                    .duration
+                    .parse::<Duration>()
+                    .unwrap_or(Duration::ZERO)
+            })
+            .sum::<Duration>()
+            .saturating_sub(start.unwrap_or(Duration::ZERO));
+
+        self.subtotals.push(Subtotal {
+            label: label.to_string(),
+            duration,
+            items: videos.len(),
+            duplicates,
+            start,
+        });
+        Ok(())
+    }
+
+    /// Marks the videos as counted, returning those that weren't yet along with the number of duplicates.
+    fn claim(&mut self, ids: Vec<String>) -> (Vec<String>, usize) {
+        let total = ids.len();
+        let fresh: Vec<String> = ids
+            .into_iter()
+            .filter(|id| self.seen.insert(id.clone()))
+            .collect();
+        let duplicates = total - fresh.len();
+
+        (fresh, duplicates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|i| i.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Source::parse("45m"),
+            Some(Source::Duration((Duration::from_secs(45 * 60), 1)))
+        );
+        assert_eq!(
+            Source::parse("12*45m"),
+            Some(Source::Duration((Duration::from_secs(12 * 45 * 60), 12)))
+        );
+        assert_eq!(
+            Source::parse("https://youtu.be/ZNYRjxJ3sdY"),
+            Some(Source::Youtube(YoutubeId::Video {
+                id: "ZNYRjxJ3sdY".to_string(),
+                start: None,
+            }))
+        );
+        assert_eq!(Source::parse("reading"), None);
+    }
+
+    #[test]
+    fn test_claim() {
+        let mut tally = Tally::default();
+
+        assert_eq!(
+            tally.claim(ids(&["a", "b", "c"])),
+            (ids(&["a", "b", "c"]), 0)
+        );
+        assert_eq!(tally.claim(ids(&["c", "d", "a"])), (ids(&["d"]), 2));
+        // repeated within a single playlist
+        assert_eq!(tally.claim(ids(&["e", "e"])), (ids(&["e"]), 1));
+    }
+
+    #[test]
+    fn test_totals() {
+        let mut tally = Tally::default();
+        tally.add_duration("45m", (Duration::from_secs(45 * 60), 1));
+        tally.add_duration("2*30m", (Duration::from_secs(60 * 60), 2));
+        tally.subtotals.push(Subtotal {
+            label: "playlist".to_string(),
+            duration: Duration::from_secs(600),
+            items: 3,
+            duplicates: 2,
+            start: None,
+        });
+
+        assert_eq!(tally.total(), Duration::from_secs(45 * 60 + 60 * 60 + 600));
+        assert_eq!(tally.items(), 6);
+        assert_eq!(tally.duplicates(), 2);
+    }
+}
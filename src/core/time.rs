@@ -1,134 +1,900 @@
-use chrono::{Datelike, TimeZone};
+use std::{fmt::Display, iter::Peekable, ops::Range, str::CharIndices};
 
-use crate::errors::TTimeError;
+use chrono::{DateTime, LocalResult, NaiveDateTime, NaiveTime, TimeDelta, TimeZone, Utc};
+
+use crate::{
+    core::{duration::Duration, expr, format::TimeFormat},
+    errors::{Diagnostic, TTimeError},
+};
 
 pub struct TimeConfig {
-    pub duration: f64,
+    pub segments: Vec<Segment>,
+    /// The multiplier used for segments without one of their own.
+    pub multiplier: f64,
+    /// The breaks taken while watching, if any.
+    pub breaks: Option<BreakPolicy>,
+}
+
+/// A fixed break taken after every block of watching, e.g. 5 minutes every 25 minutes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreakPolicy {
+    pub every: Duration,
+    pub length: Duration,
+}
+
+impl BreakPolicy {
+    /// The classic pomodoro cycle of 25 minutes of work and a 5-minute break.
+    pub const POMODORO: BreakPolicy = BreakPolicy {
+        every: Duration::from_secs(25 * 60),
+        length: Duration::from_secs(5 * 60),
+    };
+
+    /// Returns the total time spent on breaks while watching for the given duration.
+    ///
+    /// No break is taken after the last block, so exactly 50 minutes with a break every 25 minutes only
+    /// includes a single break.
+    #[must_use]
+    pub fn breaks_for(&self, watch: Duration) -> Duration {
+        if self.every.is_zero() || watch.is_zero() {
+            return Duration::ZERO;
+        }
+
+        let count = (watch.as_millis() - 1) / self.every.as_millis();
+        Duration::from_millis(self.length.as_millis().saturating_mul(count))
+    }
+
+    /// Returns the real elapsed time of watching for the given duration, including breaks.
+    #[must_use]
+    pub fn session_length(&self, watch: Duration) -> Duration {
+        watch.saturating_add(self.breaks_for(watch))
+    }
+
+    /// Returns the longest watch time whose session, including breaks, fits within the given time.
+    #[must_use]
+    pub fn watch_time_within(&self, session: Duration) -> Duration {
+        // the session length only grows with the watch time, so this can be searched for
+        let (mut low, mut high) = (0, session.as_millis());
+        while low < high {
+            let mid = low + (high - low).div_ceil(2);
+
+            if self.session_length(Duration::from_millis(mid)) <= session {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+
+        Duration::from_millis(low)
+    }
+}
+
+/// A part of a duration expression, optionally played at its own speed (e.g. `1h@1.25x`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    pub duration: Duration,
+    pub multiplier: Option<f64>,
+    /// The number of durations this segment stands for (e.g. 12 for `12*45m`).
+    pub count: u64,
+}
+
+/// A single segment after trimming.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrimmedSegment {
+    pub original: Duration,
     pub multiplier: f64,
-    pub splits: i64,
+    pub duration: Duration,
+    pub saved: Duration,
+    pub added: Duration,
+    pub count: u64,
+    /// Whether the multiplier was given for this segment specifically (rather than being the default).
+    pub annotated: bool,
+}
+
+/// The outcome of trimming a duration with a multiplier.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrimResult {
+    /// The duration after applying the multiplier.
+    pub duration: Duration,
+    /// The time saved compared to watching at 1x.
+    pub saved: Duration,
+    /// The time added compared to watching at 1x (for slowed-down content).
+    pub added: Duration,
+    /// The number of segments the original duration was made of.
+    pub splits: u64,
+    /// The real elapsed time, i.e. the trimmed duration plus any breaks.
+    pub session: Duration,
+    /// The per-segment breakdown of the result.
+    pub segments: Vec<TrimmedSegment>,
 }
 
 impl TimeConfig {
     pub fn new(duration: &str, multiplier_user: &str) -> Result<TimeConfig, TTimeError> {
-        let duration_tuple = parse_duration(duration)?;
-        let multiplier_value = parse_multiplier(multiplier_user)?;
+        Self::with_range(duration, multiplier_user, &MultiplierRange::default())
+    }
+
+    /// Same as [`TimeConfig::new`], but checks every multiplier against the given range.
+    pub fn with_range(
+        duration: &str,
+        multiplier_user: &str,
+        range: &MultiplierRange,
+    ) -> Result<TimeConfig, TTimeError> {
+        let segments = parse_segments(duration)?;
+        let multiplier = range.check(parse_multiplier(multiplier_user)?)?;
+
+        for m in segments.iter().filter_map(|s| s.multiplier) {
+            range.check(m)?;
+        }
 
         Ok(TimeConfig {
-            duration: duration_tuple.0,
-            multiplier: multiplier_value,
-            splits: duration_tuple.1,
+            segments,
+            multiplier,
+            breaks: None,
         })
     }
 
-    pub fn trim(&self) -> Result<(f64, f64, i64), TTimeError> {
-        let old_duration = self.duration;
-        let multiplier = self.multiplier;
+    /// Returns the same config with the given breaks taken into account.
+    #[must_use]
+    pub fn with_breaks(self, breaks: Option<BreakPolicy>) -> Self {
+        Self { breaks, ..self }
+    }
+
+    /// The total duration of all segments.
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.segments.iter().map(|s| s.duration).sum()
+    }
+
+    /// The total number of durations described by the segments.
+    #[must_use]
+    pub fn splits(&self) -> u64 {
+        self.segments.iter().map(|s| s.count).sum()
+    }
 
-        let new_duration = old_duration / multiplier;
-        let saved_time = old_duration - new_duration;
+    pub fn trim(&self) -> Result<TrimResult, TTimeError> {
+        let segments = self
+            .segments
+            .iter()
+            .map(|s| {
+                let multiplier = s.multiplier.unwrap_or(self.multiplier);
+                let duration = s
+                    .duration
+                    .checked_div_f64(multiplier)
+                    .ok_or(TTimeError::DurationOverflow)?;
 
-        Ok((new_duration, saved_time, self.splits))
+                Ok(TrimmedSegment {
+                    original: s.duration,
+                    multiplier,
+                    duration,
+                    saved: s.duration.saturating_sub(duration),
+                    added: duration.saturating_sub(s.duration),
+                    count: s.count,
+                    annotated: s.multiplier.is_some(),
+                })
+            })
+            .collect::<Result<Vec<_>, TTimeError>>()?;
+
+        let original = self.duration();
+        let duration: Duration = segments.iter().map(|s| s.duration).sum();
+
+        Ok(TrimResult {
+            duration,
+            saved: original.saturating_sub(duration),
+            added: duration.saturating_sub(original),
+            splits: self.splits(),
+            session: self
+                .breaks
+                .map(|b| b.session_length(duration))
+                .unwrap_or(duration),
+            segments,
+        })
     }
 }
 
-fn parse_multiplier(multiplier_user: &str) -> Result<f64, TTimeError> {
+/// The inclusive range of accepted multipliers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MultiplierRange {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Default for MultiplierRange {
+    fn default() -> Self {
+        Self {
+            min: 0.25,
+            max: 100.0,
+        }
+    }
+}
+
+impl MultiplierRange {
+    /// Returns the multiplier if it lies within this range.
+    pub fn check(&self, multiplier: f64) -> Result<f64, TTimeError> {
+        if (self.min..=self.max).contains(&multiplier) {
+            Ok(multiplier)
+        } else {
+            Err(TTimeError::MultiplierOutOfRange((self.min, self.max)))
+        }
+    }
+}
+
+/// Parses a multiplier such as `1.25x` or `1.25`, written as a plain decimal number (no sign or exponent). The
+/// value is not checked against any [`MultiplierRange`].
+pub(crate) fn parse_multiplier(multiplier_user: &str) -> Result<f64, TTimeError> {
+    let invalid = || {
+        TTimeError::InvalidMultiplierFormat(
+            Diagnostic::new(multiplier_user, 0..multiplier_user.len())
+                .with_expected(["a positive number (e.g. `1.25x`)"]),
+        )
+    };
+
     let multiplier = if let Some(stripped) = multiplier_user.strip_suffix('x') {
         stripped
     } else {
         multiplier_user
     };
 
-    let multiplier_value: f64 = multiplier
-        .parse()
-        .map_err(|_| TTimeError::InvalidMultiplierFormat)?;
+    // `f64` would also take e.g. `1e2` or `inf`
+    if !multiplier.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return Err(invalid());
+    }
+
+    let multiplier_value: f64 = multiplier.parse().map_err(|_| invalid())?;
 
-    if !(1.0..100.0).contains(&multiplier_value) {
-        Err(TTimeError::MultiplierOutOfRange)
+    if !multiplier_value.is_finite() || multiplier_value <= 0.0 {
+        Err(invalid())
     } else {
         Ok(multiplier_value)
     }
 }
 
+/// Returns the multiplier needed to watch content of the given duration within a budget. With breaks, the
+/// session including them has to fit, which leaves less of the budget for watching.
+///
+/// Returns `None` if no time is left for watching, since no multiplier would suffice.
+#[must_use]
+pub fn required_multiplier(
+    duration: Duration,
+    budget: Duration,
+    breaks: Option<BreakPolicy>,
+) -> Option<f64> {
+    let watch = breaks.map_or(budget, |b| b.watch_time_within(budget));
+
+    if watch.is_zero() {
+        None
+    } else {
+        Some(duration.as_secs_f64() / watch.as_secs_f64())
+    }
+}
+
+/// Returns the multiplier to suggest for a required one: rounded up to the next player step, but never below
+/// 1x, since content that already fits the budget needs no slowing down.
 #[must_use]
-pub fn parse_time(time: f64) -> String {
-    let mut time_string = String::new();
-
-    let days = time as u64 / 86400;
-    let hours = (time as u64 % 86400) / 3600;
-    let minutes = (time as u64 % 3600) / 60;
-    let seconds = time as u64 % 60;
-
-    for (i, time) in [days, hours, minutes, seconds].iter().enumerate() {
-        if *time != 0 {
-            time_string.push_str(&format!(
-                "{}{}",
-                time,
-                match i {
-                    0 => "d",
-                    1 => "h",
-                    2 => "m",
-                    3 => "s",
-                    _ => "",
+pub fn suggested_multiplier(required: f64, step: f64) -> f64 {
+    round_up_to_step(required, step).max(1.0)
+}
+
+/// Rounds a multiplier up to the next step a video player offers (e.g. 0.05 or 0.25).
+#[must_use]
+pub fn round_up_to_step(multiplier: f64, step: f64) -> f64 {
+    // the epsilon keeps exact steps (e.g. 1.5 / 0.05) from being bumped up by float error
+    let steps = (multiplier / step - 1e-9).ceil();
+    (steps * step * 1e6).round() / 1e6
+}
+
+/// Formats a duration in compact notation (e.g. `1d1h1m1s`), truncated to whole seconds. Zero is formatted as
+/// an empty string.
+///
+/// See [`TimeFormat`] for other units, rounding modes and a long format.
+#[must_use]
+pub fn parse_time(time: Duration) -> String {
+    if time.as_secs() == 0 {
+        String::new()
+    } else {
+        TimeFormat::default().format(time)
+    }
+}
+
+/// Parses a duration expression into its total duration and the number of segments it describes.
+///
+/// Durations may be written with unit suffixes (`1h2m3s`), in clock notation (`1:02:03`, `45:10.5`) or as
+/// ISO 8601 durations (`PT1H2M`, `P1DT2H`, `P2W`), and can be combined with `+`, `-`, `*`, `/` and
+/// parentheses, e.g. `12*45m` or `(1h30m + 50m) * 2`. See [`crate::core::expr`] for the details.
+///
+/// Since a plain duration has no speed, per-segment multipliers (`@2x`) are rejected here; use
+/// [`parse_segments`] to accept them.
+pub fn parse_duration(duration: &str) -> Result<(Duration, u64), TTimeError> {
+    let segments = parse_segments(duration)?;
+
+    if segments.iter().any(|s| s.multiplier.is_some()) {
+        return Err(TTimeError::UnexpectedMultiplier);
+    }
+
+    Ok((
+        segments.iter().map(|s| s.duration).sum(),
+        segments.iter().map(|s| s.count).sum(),
+    ))
+}
+
+/// Parses a duration expression into its segments, keeping per-segment multipliers such as `1h@1.25x`.
+pub fn parse_segments(duration: &str) -> Result<Vec<Segment>, TTimeError> {
+    expr::evaluate(duration)
+}
+
+/// Parses a single duration literal (without any operators) into seconds.
+pub(crate) fn parse_literal(literal: &str) -> Result<f64, TTimeError> {
+    if literal.contains(':') {
+        parse_clock(literal)
+    } else if literal.starts_with(['P', 'p']) {
+        parse_iso8601(literal)
+    } else {
+        parse_units(literal)
+    }
+}
+
+/// The unit designators understood by [`parse_units`], as listed in diagnostics.
+pub(crate) const UNITS: [&str; 4] = ["`s`", "`m`", "`h`", "`d`"];
+
+/// Spelled-out units accepted in place of a designator (matched case-insensitively), e.g. `90 min`.
+const UNIT_WORDS: &[(&str, &str)] = &[
+    ("sec", "s"),
+    ("secs", "s"),
+    ("second", "s"),
+    ("seconds", "s"),
+    ("min", "m"),
+    ("mins", "m"),
+    ("minute", "m"),
+    ("minutes", "m"),
+    ("hr", "h"),
+    ("hrs", "h"),
+    ("hour", "h"),
+    ("hours", "h"),
+    ("day", "d"),
+    ("days", "d"),
+];
+
+/// Returns the number of seconds in a unit, given as a designator (`m`) or a unit word (`minutes`).
+fn unit_seconds(unit: &str) -> Option<f64> {
+    let lowercase = unit.to_lowercase();
+    let designator = UNIT_WORDS
+        .iter()
+        .find(|(word, _)| *word == lowercase)
+        .map_or(unit, |(_, designator)| *designator);
+
+    match designator {
+        "s" => Some(1.0),
+        "m" => Some(60.0),
+        "h" => Some(3600.0),
+        "d" => Some(86400.0),
+        _ => None,
+    }
+}
+
+/// Consumes characters as long as they match, returning the end of the consumed run.
+fn take_while(chars: &mut Peekable<CharIndices<'_>>, mut end: usize, f: fn(char) -> bool) -> usize {
+    while let Some((i, c)) = chars.next_if(|&(_, c)| f(c)) {
+        end = i + c.len_utf8();
+    }
+
+    end
+}
+
+/// Parses unit-suffixed notation, e.g. `1d1h1m1s`, `1.5h` or `2 hours, 5 minutes and 10 seconds`.
+///
+/// Components may be separated by whitespace, commas or the word `and`.
+fn parse_units(part: &str) -> Result<f64, TTimeError> {
+    let expected_number = |span: Range<usize>| {
+        TTimeError::InvalidDurationFormat(Diagnostic::new(part, span).with_expected(["a number"]))
+    };
+
+    let mut part_seconds = 0f64;
+    let mut components = 0;
+    // a `,` or `and` must be followed by another component
+    let mut separated = false;
+    let mut chars = part.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+
+        if c.is_ascii_digit() || c == '.' {
+            let number_end = take_while(&mut chars, start + 1, |c| c.is_ascii_digit() || c == '.');
+            take_while(&mut chars, number_end, char::is_whitespace);
+
+            let unit = match chars.next() {
+                Some((i, c)) if c.is_alphabetic() => {
+                    i..take_while(&mut chars, i + c.len_utf8(), char::is_alphabetic)
+                }
+                Some((i, c)) => i..i + c.len_utf8(),
+                None => {
+                    return Err(TTimeError::InvalidDurationFormat(
+                        Diagnostic::new(part, number_end..number_end).with_expected(UNITS),
+                    ));
                 }
+            };
+
+            let Some(factor) = unit_seconds(&part[unit.clone()]) else {
+                return Err(unknown_unit(part, unit));
+            };
+
+            let number: f64 = part[start..number_end]
+                .parse()
+                .map_err(|_| TTimeError::InvalidNumber(Diagnostic::new(part, start..number_end)))?;
+
+            part_seconds += number * factor;
+            components += 1;
+            separated = false;
+            continue;
+        }
+
+        let end = if c.is_alphabetic() {
+            take_while(&mut chars, start + c.len_utf8(), char::is_alphabetic)
+        } else {
+            start + c.len_utf8()
+        };
+        let word = &part[start..end];
+
+        if c == ',' || word.eq_ignore_ascii_case("and") {
+            if components == 0 || separated {
+                return Err(expected_number(start..end));
+            }
+            separated = true;
+        } else if unit_seconds(word).is_some() {
+            return Err(expected_number(start..end));
+        } else {
+            return Err(unknown_unit(part, start..end));
+        }
+    }
+
+    if components == 0 || separated {
+        return Err(expected_number(part.len()..part.len()));
+    }
+
+    Ok(part_seconds)
+}
+
+/// The number of single-character insertions, deletions and substitutions needed to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (diagonal + usize::from(ca != *cb))
+                .min(row[j] + 1)
+                .min(above + 1);
+            diagonal = above;
+        }
+    }
+
+    row[b.len()]
+}
+
+/// Builds the error for an unknown unit, suggesting the closest known unit for typos (`minuts`, `H`, ...).
+fn unknown_unit(part: &str, span: Range<usize>) -> TTimeError {
+    let unit = &part[span.clone()];
+    let lowercase = unit.to_lowercase();
+
+    let suggestion = if unit_seconds(&lowercase).is_some() {
+        Some(lowercase.as_str())
+    } else {
+        let tolerance = if lowercase.chars().count() > 4 { 2 } else { 1 };
+
+        UNIT_WORDS
+            .iter()
+            .map(|(word, _)| (edit_distance(&lowercase, word), *word))
+            .filter(|(distance, _)| *distance <= tolerance)
+            // on a tie, keep the plural (or singular) form the unit was typed in
+            .min_by_key(|(distance, word)| {
+                (*distance, word.ends_with('s') != lowercase.ends_with('s'))
+            })
+            .map(|(_, word)| word)
+    };
+
+    if let Some(suggestion) = suggestion {
+        return TTimeError::InvalidTimeUnit(
+            Diagnostic::new(part, span)
+                .with_expected(UNITS)
+                .with_suggestion(suggestion),
+        );
+    }
+
+    // otherwise point at the first character that cannot be part of a unit, e.g. the `n` in `1hn`
+    let mut chars = unit.char_indices().map(|(i, c)| (span.start + i, c));
+    match (chars.next(), chars.next()) {
+        (Some((_, first)), Some((i, c))) if unit_seconds(&first.to_string()).is_some() => {
+            TTimeError::InvalidDurationFormat(
+                Diagnostic::new(part, i..i + c.len_utf8()).with_expected(["a number"]),
+            )
+        }
+        (Some((i, c)), _) => TTimeError::InvalidTimeUnit(
+            Diagnostic::new(part, i..i + c.len_utf8()).with_expected(UNITS),
+        ),
+        _ => TTimeError::InvalidTimeUnit(Diagnostic::new(part, span).with_expected(UNITS)),
+    }
+}
+
+/// Parses clock notation as shown by video players, e.g. `1:02:03`, `45:10` or `01:02:03.500`.
+///
+/// Only the last (seconds) field may be fractional, and every field but the first must be below 60.
+fn parse_clock(part: &str) -> Result<f64, TTimeError> {
+    let fields: Vec<&str> = part.split(':').collect();
+
+    if !(2..=3).contains(&fields.len()) {
+        return Err(TTimeError::InvalidDurationFormat(
+            Diagnostic::new(part, 0..part.len()).with_expected(["`M:SS`", "`H:MM:SS`"]),
+        ));
+    }
+
+    let mut total = 0f64;
+    let mut start = 0;
+
+    for (i, field) in fields.iter().enumerate() {
+        let is_last = i == fields.len() - 1;
+        let span = start..start + field.len();
+        start = span.end + 1;
+
+        if field.is_empty() {
+            return Err(TTimeError::InvalidDurationFormat(
+                Diagnostic::new(part, span).with_expected(["a number"]),
+            ));
+        }
+
+        if let Some((j, c)) = field
+            .char_indices()
+            .find(|(_, c)| !(c.is_ascii_digit() || (is_last && *c == '.')))
+        {
+            let at = span.start + j;
+            return Err(TTimeError::InvalidDurationFormat(
+                Diagnostic::new(part, at..at + c.len_utf8()).with_expected(["a digit"]),
             ));
         }
+
+        let value: f64 = field
+            .parse()
+            .map_err(|_| TTimeError::InvalidNumber(Diagnostic::new(part, span.clone())))?;
+
+        if i != 0 && value >= 60.0 {
+            return Err(TTimeError::InvalidDurationFormat(
+                Diagnostic::new(part, span).with_expected(["a value below 60"]),
+            ));
+        }
+
+        total = total * 60.0 + value;
     }
 
-    time_string
+    Ok(total)
 }
 
-pub fn parse_duration(duration: &str) -> Result<(f64, i64), TTimeError> {
-    let mut total_seconds = 0f64;
-    let mut splits = 0;
+/// Parses an ISO 8601 duration such as `PT1H2M3S`, `P1DT2H` or `P1W`.
+///
+/// Years and months are rejected since their length in seconds is ambiguous.
+fn parse_iso8601(part: &str) -> Result<f64, TTimeError> {
+    let invalid = |span: Range<usize>, expected: Vec<String>| {
+        TTimeError::InvalidDurationFormat(Diagnostic::new(part, span).with_expected(expected))
+    };
+    let designators = |units: &str| units.chars().map(|u| format!("`{u}`")).collect::<Vec<_>>();
+
+    // uppercasing ASCII keeps byte offsets intact
+    let upper = part.to_ascii_uppercase();
+    let Some(body) = upper.strip_prefix('P') else {
+        return Err(invalid(0..1, vec!["`P`".to_string()]));
+    };
+
+    let (date, time) = match body.split_once('T') {
+        Some((_, "")) => {
+            return Err(invalid(
+                part.len()..part.len(),
+                vec!["a number".to_string()],
+            ));
+        }
+        Some((d, t)) => (d, Some(t)),
+        None => (body, None),
+    };
 
-    for part in duration.split('+') {
-        let mut current_number = String::new();
-        let mut part_seconds = 0f64;
+    if date.is_empty() && time.is_none() {
+        return Err(invalid(
+            part.len()..part.len(),
+            vec!["a number".to_string(), "`T`".to_string()],
+        ));
+    }
 
-        for c in part.chars() {
-            if c.is_ascii_digit() || c == '.' {
-                current_number.push(c);
-            } else if c.is_whitespace() {
+    let mut total = 0f64;
+
+    for (section, offset, units) in [
+        (date, 1, "WD"),
+        (time.unwrap_or_default(), date.len() + 2, "HMS"),
+    ] {
+        let mut number_start = 0;
+        // tracks designator order so that e.g. `PT1S2H` is refused
+        let mut last_unit: Option<usize> = None;
+
+        for (i, c) in section.char_indices() {
+            if c.is_ascii_digit() || c == '.' || c == ',' {
                 continue;
-            } else {
-                let number: f64 = current_number
-                    .parse()
-                    .map_err(|_| TTimeError::NegativeDuration)?;
-                current_number.clear();
-                part_seconds += match c {
-                    's' => number,
-                    'm' => number * 60.0,
-                    'h' => number * 3600.0,
-                    'd' => number * 86400.0,
-                    _ => return Err(TTimeError::InvalidTimeUnit),
+            }
+
+            let span = offset + i..offset + i + c.len_utf8();
+            let remaining = &units[last_unit.map_or(0, |l| l + 1)..];
+
+            let Some(position) = units.find(c) else {
+                return Err(invalid(span, designators(remaining)));
+            };
+
+            if last_unit.is_some_and(|l| l >= position) {
+                return Err(invalid(span, designators(remaining)));
+            }
+            last_unit = Some(position);
+
+            if number_start == i {
+                return Err(invalid(span, vec!["a number".to_string()]));
+            }
+
+            let number: f64 = section[number_start..i]
+                .replace(',', ".")
+                .parse()
+                .map_err(|_| {
+                    TTimeError::InvalidNumber(Diagnostic::new(
+                        part,
+                        offset + number_start..offset + i,
+                    ))
+                })?;
+            number_start = i + c.len_utf8();
+
+            total += number
+                * match c {
+                    'W' => 604800.0,
+                    'D' => 86400.0,
+                    'H' => 3600.0,
+                    'M' => 60.0,
+                    _ => 1.0,
                 };
+        }
+
+        if number_start != section.len() {
+            let end = offset + section.len();
+            let remaining = &units[last_unit.map_or(0, |l| l + 1)..];
+            return Err(invalid(end..end, designators(remaining)));
+        }
+    }
+
+    Ok(total)
+}
+
+/// The part of the day available for watching content.
+///
+/// Without a start, the day simply ends at the next occurrence of `end`. With a start, the day is the window
+/// from `start` to `end`, which may cross midnight (e.g. 07:00 to 01:00).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DayBounds {
+    pub start: Option<NaiveTime>,
+    pub end: NaiveTime,
+}
+
+impl Default for DayBounds {
+    fn default() -> Self {
+        Self {
+            start: None,
+            end: NaiveTime::from_hms_opt(23, 59, 59).unwrap_or(NaiveTime::MIN),
+        }
+    }
+}
+
+impl DayBounds {
+    /// Returns the time left in the day at the given instant, in the instant's timezone.
+    ///
+    /// Before the day has started, the full length of the upcoming day is available. Durations are measured
+    /// in elapsed time, so days with a DST transition are correspondingly shorter or longer.
+    #[must_use]
+    pub fn time_left<Tz: TimeZone>(&self, now: &DateTime<Tz>) -> Duration {
+        let tz = now.timezone();
+        let local_now = now.naive_local();
+        let today = local_now.date();
+        let elapsed = |from: &DateTime<Tz>, to: &DateTime<Tz>| {
+            to.to_utc()
+                .signed_duration_since(from.to_utc())
+                .to_std()
+                .map(Duration::from)
+                .unwrap_or_default()
+        };
+
+        let Some(start) = self.start else {
+            let mut end = today.and_time(self.end);
+            if end <= local_now {
+                end += TimeDelta::days(1);
+            }
+            return elapsed(now, &resolve_local(&tz, end));
+        };
+
+        let window_length = if self.end > start {
+            self.end - start
+        } else {
+            self.end - start + TimeDelta::days(1)
+        };
+
+        for day in [
+            today - TimeDelta::days(1),
+            today,
+            today + TimeDelta::days(1),
+        ] {
+            let window_start = resolve_local(&tz, day.and_time(start));
+            let window_end = resolve_local(&tz, day.and_time(start) + window_length);
+
+            if *now < window_start {
+                // the day has not started yet, so all of it is available
+                return elapsed(&window_start, &window_end);
+            } else if *now < window_end {
+                return elapsed(now, &window_end);
             }
         }
 
-        if !current_number.is_empty() {
-            return Err(TTimeError::InvalidDurationFormat);
+        Duration::ZERO
+    }
+}
+
+/// Turns a local wall-clock time into an instant, without panicking around DST transitions.
+///
+/// Ambiguous times (when clocks go back) resolve to the earlier instant, and times skipped over (when clocks
+/// go forward) resolve to the first valid time after the gap.
+fn resolve_local<Tz: TimeZone>(tz: &Tz, local: NaiveDateTime) -> DateTime<Tz> {
+    let mut candidate = local;
+
+    // gaps are at most a few hours long, so stepping forward is bound to find a valid time
+    for _ in 0..(24 * 4) {
+        match tz.from_local_datetime(&candidate) {
+            LocalResult::Single(t) => return t,
+            LocalResult::Ambiguous(earliest, _) => return earliest,
+            LocalResult::None => candidate += TimeDelta::minutes(15),
+        }
+    }
+
+    tz.from_utc_datetime(&local)
+}
+
+/// The timezone used for day calculations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Zone {
+    /// The system's local timezone.
+    #[default]
+    Local,
+    /// An IANA timezone, e.g. `Europe/Berlin`.
+    Named(chrono_tz::Tz),
+}
+
+/// Everything needed to reason about "today": its boundaries, the timezone, and the current instant.
+///
+/// The current instant is captured on creation, and may be replaced using [`DayContext::at`] (e.g. for tests).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DayContext {
+    pub bounds: DayBounds,
+    pub zone: Zone,
+    pub now: DateTime<Utc>,
+    /// A planned start time for the session; the session starts right away if unset.
+    pub start_at: Option<NaiveTime>,
+}
+
+/// The wall-clock time at which something finishes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FinishTime {
+    pub at: NaiveDateTime,
+    /// The number of days after today (0 if finishing today).
+    pub day_offset: i64,
+}
+
+impl Display for FinishTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.at.format("%H:%M"))?;
+
+        if self.day_offset > 0 {
+            write!(f, " (+{}d)", self.day_offset)?;
         }
 
-        total_seconds += part_seconds;
-        splits += 1;
+        Ok(())
     }
+}
 
-    Ok((total_seconds, splits))
+impl Default for DayContext {
+    fn default() -> Self {
+        Self::new(DayBounds::default(), Zone::Local)
+    }
 }
 
-#[must_use]
-pub fn time_in_day_after(duration: f64) -> f64 {
-    let now = chrono::Local::now();
-    let end_of_day = chrono::Local
-        .with_ymd_and_hms(now.year(), now.month(), now.day(), 23, 59, 59)
-        .unwrap();
-    let time_passed = end_of_day.signed_duration_since(now).num_seconds() as f64;
+impl DayContext {
+    #[must_use]
+    pub fn new(bounds: DayBounds, zone: Zone) -> Self {
+        Self {
+            bounds,
+            zone,
+            now: Utc::now(),
+            start_at: None,
+        }
+    }
 
-    if time_passed > duration {
-        time_passed - duration
-    } else {
-        0.0
+    /// Returns the same context with the current instant replaced.
+    #[must_use]
+    pub fn at(self, now: DateTime<Utc>) -> Self {
+        Self { now, ..self }
+    }
+
+    /// Returns the same context with a planned session start.
+    #[must_use]
+    pub fn with_start_at(self, start_at: Option<NaiveTime>) -> Self {
+        Self { start_at, ..self }
+    }
+
+    /// Returns the local wall-clock time of an instant.
+    #[must_use]
+    pub fn local(&self, instant: DateTime<Utc>) -> NaiveDateTime {
+        match self.zone {
+            Zone::Local => instant.with_timezone(&chrono::Local).naive_local(),
+            Zone::Named(tz) => instant.with_timezone(&tz).naive_local(),
+        }
+    }
+
+    /// Returns the instant a session starts: now, or the next occurrence of the planned start time.
+    #[must_use]
+    pub fn session_start(&self) -> DateTime<Utc> {
+        let Some(start_at) = self.start_at else {
+            return self.now;
+        };
+
+        let local_now = self.local(self.now);
+        let mut start = local_now.date().and_time(start_at);
+        if start < local_now {
+            start += TimeDelta::days(1);
+        }
+
+        match self.zone {
+            Zone::Local => resolve_local(&chrono::Local, start).to_utc(),
+            Zone::Named(tz) => resolve_local(&tz, start).to_utc(),
+        }
     }
+
+    /// Returns the wall-clock time at which a session of the given length finishes, if representable.
+    #[must_use]
+    pub fn finish_time(&self, duration: Duration) -> Option<FinishTime> {
+        let elapsed = TimeDelta::try_milliseconds(i64::try_from(duration.as_millis()).ok()?)?;
+        let at = self.local(self.session_start().checked_add_signed(elapsed)?);
+
+        Some(FinishTime {
+            at,
+            day_offset: (at.date() - self.local(self.now).date()).num_days(),
+        })
+    }
+
+    /// Returns the time left in the day, counted from the start of the session.
+    #[must_use]
+    pub fn time_left(&self) -> Duration {
+        let start = self.session_start();
+
+        match self.zone {
+            Zone::Local => self.bounds.time_left(&start.with_timezone(&chrono::Local)),
+            Zone::Named(tz) => self.bounds.time_left(&start.with_timezone(&tz)),
+        }
+    }
+}
+
+/// Parses an IANA timezone name such as `Asia/Dhaka`.
+pub fn parse_timezone(name: &str) -> Result<chrono_tz::Tz, TTimeError> {
+    name.parse()
+        .map_err(|_| TTimeError::InvalidTimezone(name.to_string()))
+}
+
+/// Parses a time of day in `HH:MM` or `HH:MM:SS` notation.
+pub fn parse_clock_time(time: &str) -> Result<NaiveTime, TTimeError> {
+    NaiveTime::parse_from_str(time, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+        .map_err(|_| TTimeError::InvalidTimeOfDay(time.to_string()))
+}
+
+/// Returns how much of the day would be left after spending the given duration.
+#[must_use]
+pub fn time_in_day_after(duration: Duration, day: &DayContext) -> Duration {
+    day.time_left().saturating_sub(duration)
 }
 
 #[cfg(test)]
@@ -138,25 +904,147 @@ mod tests {
 
     #[test]
     fn test_parse_duration() {
-        assert_eq!(parse_duration("1s").unwrap(), (1.0, 1));
-        assert_eq!(parse_duration("1m").unwrap(), (60.0, 1));
-        assert_eq!(parse_duration("1h").unwrap(), (3600.0, 1));
-        assert_eq!(parse_duration("1d").unwrap(), (86400.0, 1));
-        assert_eq!(parse_duration("1d1h1m1s").unwrap(), (90061.0, 1));
-        assert_eq!(parse_duration("1h+1m+1s").unwrap(), (3661.0, 3));
-        assert_eq!(parse_duration("1.5h").unwrap(), (5400.0, 1));
-        assert_eq!(parse_duration("1.5h+30m").unwrap(), (7200.0, 2));
+        assert_eq!(parse_duration("1s").unwrap(), (Duration::from_secs(1), 1));
+        assert_eq!(parse_duration("1m").unwrap(), (Duration::from_secs(60), 1));
+        assert_eq!(
+            parse_duration("1h").unwrap(),
+            (Duration::from_secs(3600), 1)
+        );
+        assert_eq!(
+            parse_duration("1d").unwrap(),
+            (Duration::from_secs(86400), 1)
+        );
+        assert_eq!(
+            parse_duration("1d1h1m1s").unwrap(),
+            (Duration::from_secs(90061), 1)
+        );
+        assert_eq!(
+            parse_duration("1h+1m+1s").unwrap(),
+            (Duration::from_secs(3661), 3)
+        );
+        assert_eq!(
+            parse_duration("1.5h").unwrap(),
+            (Duration::from_secs(5400), 1)
+        );
+        assert_eq!(
+            parse_duration("1.5h+30m").unwrap(),
+            (Duration::from_secs(7200), 2)
+        );
         assert!(parse_duration("1x").is_err());
         assert!(parse_duration("1").is_err());
+        assert_eq!(
+            parse_duration("12*45m").unwrap(),
+            (Duration::from_secs(32400), 12)
+        );
+        assert_eq!(
+            parse_duration("3h - 20m").unwrap(),
+            (Duration::from_secs(9600), 1)
+        );
+    }
+
+    #[test]
+    fn test_parse_duration_words() {
+        for (input, secs) in [
+            ("1 hour 30 minutes", 5400),
+            ("90 min", 5400),
+            ("1.5 hrs", 5400),
+            ("2 hours and 5 mins", 7500),
+            ("1 Day, 2 Hours, 3 Minutes and 4 Seconds", 93784),
+            ("1h 30m", 5400),
+            ("1 sec", 1),
+        ] {
+            assert_eq!(
+                parse_duration(input).unwrap(),
+                (Duration::from_secs(secs), 1),
+                "{input}"
+            );
+        }
+
+        assert_eq!(
+            parse_duration("1 hour + 2 * 45 minutes").unwrap(),
+            (Duration::from_secs(9000), 3)
+        );
+        assert!(parse_duration("and 1h").is_err());
+        assert!(parse_duration("1h and").is_err());
+        assert!(parse_duration("1h,,2m").is_err());
+        assert!(parse_duration("1 5m").is_err());
+        assert!(parse_duration("hours").is_err());
+        assert!(parse_duration("1 fortnight").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration_clock() {
+        assert_eq!(
+            parse_duration("45:10").unwrap(),
+            (Duration::from_secs(2710), 1)
+        );
+        assert_eq!(
+            parse_duration("1:02:03").unwrap(),
+            (Duration::from_secs(3723), 1)
+        );
+        assert_eq!(
+            parse_duration("01:02:03.500").unwrap(),
+            (Duration::from_millis(3723500), 1)
+        );
+        assert_eq!(
+            parse_duration("1:02:03+45:10").unwrap(),
+            (Duration::from_secs(6433), 2)
+        );
+        assert_eq!(
+            parse_duration("1:00:00+30m").unwrap(),
+            (Duration::from_secs(5400), 2)
+        );
+        assert!(parse_duration("1:75").is_err());
+        assert!(parse_duration("1.5:30").is_err());
+        assert!(parse_duration("1:2:3:4").is_err());
+        assert!(parse_duration(":30").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration_iso8601() {
+        assert_eq!(
+            parse_duration("PT1H2M").unwrap(),
+            (Duration::from_secs(3720), 1)
+        );
+        assert_eq!(
+            parse_duration("PT1H2M3S").unwrap(),
+            (Duration::from_secs(3723), 1)
+        );
+        assert_eq!(
+            parse_duration("P1DT2H").unwrap(),
+            (Duration::from_secs(93600), 1)
+        );
+        assert_eq!(
+            parse_duration("P1W").unwrap(),
+            (Duration::from_secs(604800), 1)
+        );
+        assert_eq!(parse_duration("P0D").unwrap(), (Duration::from_secs(0), 1));
+        assert_eq!(
+            parse_duration("pt30m").unwrap(),
+            (Duration::from_secs(1800), 1)
+        );
+        assert_eq!(
+            parse_duration("PT0.5S").unwrap(),
+            (Duration::from_millis(500), 1)
+        );
+        assert_eq!(
+            parse_duration("PT1H+45:10+10m").unwrap(),
+            (Duration::from_secs(6910), 3)
+        );
+        assert!(parse_duration("P").is_err());
+        assert!(parse_duration("PT").is_err());
+        assert!(parse_duration("P1M").is_err());
+        assert!(parse_duration("PT1S2H").is_err());
+        assert!(parse_duration("PT1").is_err());
     }
 
     #[test]
     fn test_parse_time() {
-        assert_eq!(parse_time(1.0), "1s");
-        assert_eq!(parse_time(60.0), "1m");
-        assert_eq!(parse_time(3600.0), "1h");
-        assert_eq!(parse_time(86400.0), "1d");
-        assert_eq!(parse_time(90061.0), "1d1h1m1s");
+        assert_eq!(parse_time(Duration::from_secs(1)), "1s");
+        assert_eq!(parse_time(Duration::from_secs(60)), "1m");
+        assert_eq!(parse_time(Duration::from_secs(3600)), "1h");
+        assert_eq!(parse_time(Duration::from_secs(86400)), "1d");
+        assert_eq!(parse_time(Duration::from_secs(90061)), "1d1h1m1s");
     }
 
     #[test]
@@ -170,20 +1058,390 @@ mod tests {
         assert!(TimeConfig::new("1.5h+30m", "2x").is_ok());
         assert!(TimeConfig::new("1x", "2x").is_err());
         assert!(TimeConfig::new("1", "2x").is_err());
+        assert!(TimeConfig::new("1h", "0.75x").is_ok());
+        assert!(TimeConfig::new("1h", "0.1x").is_err());
+        assert!(TimeConfig::new("1h", "0x").is_err());
+        assert!(TimeConfig::new("1h", "-2x").is_err());
+        assert!(TimeConfig::new("1h@200x", "2x").is_err());
+    }
+
+    #[test]
+    fn test_parse_multiplier() {
+        assert_eq!(parse_multiplier("1.25x").unwrap(), 1.25);
+        assert_eq!(parse_multiplier("2").unwrap(), 2.0);
+        assert_eq!(parse_multiplier(".5x").unwrap(), 0.5);
+        assert!(parse_multiplier("1e2x").is_err());
+        assert!(parse_multiplier("1E1").is_err());
+        assert!(parse_multiplier("+2x").is_err());
+        assert!(parse_multiplier("infx").is_err());
+        assert!(parse_multiplier("x").is_err());
+        assert!(TimeConfig::new("1h@1e1x", "2x").is_err());
+    }
+
+    #[test]
+    fn test_multiplier_range() {
+        let range = MultiplierRange { min: 1.0, max: 3.0 };
+
+        assert!(TimeConfig::with_range("1h", "3x", &range).is_ok());
+        assert!(matches!(
+            TimeConfig::with_range("1h", "0.75x", &range),
+            Err(TTimeError::MultiplierOutOfRange((1.0, 3.0)))
+        ));
+        assert!(TimeConfig::with_range("1h@4x", "2x", &range).is_err());
+    }
+
+    #[test]
+    fn test_trim_slowdown() {
+        let result = TimeConfig::new("1h", "0.75x").unwrap().trim().unwrap();
+
+        assert_eq!(result.duration, Duration::from_secs(4800));
+        assert_eq!(result.saved, Duration::ZERO);
+        assert_eq!(result.added, Duration::from_secs(1200));
     }
 
     #[test]
     fn test_trim() {
         let config = TimeConfig::new("1d", "2x").unwrap();
-        assert_eq!(config.trim().unwrap(), (43200.0, 43200.0, 1));
+        assert_eq!(
+            config.trim().unwrap(),
+            TrimResult {
+                duration: Duration::from_secs(43200),
+                saved: Duration::from_secs(43200),
+                added: Duration::ZERO,
+                splits: 1,
+                session: Duration::from_secs(43200),
+                segments: vec![TrimmedSegment {
+                    original: Duration::from_secs(86400),
+                    multiplier: 2.0,
+                    duration: Duration::from_secs(43200),
+                    saved: Duration::from_secs(43200),
+                    added: Duration::ZERO,
+                    count: 1,
+                    annotated: false,
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn test_trim_segments() {
+        let config = TimeConfig::new("1h@1.25x+30m@2x+45m", "1.5x").unwrap();
+        let result = config.trim().unwrap();
+
+        assert_eq!(result.splits, 3);
+        assert_eq!(result.duration, Duration::from_secs(2880 + 900 + 1800));
+        assert_eq!(result.saved, Duration::from_secs(720 + 900 + 900));
+        assert_eq!(
+            result
+                .segments
+                .iter()
+                .map(|s| (s.multiplier, s.annotated))
+                .collect::<Vec<_>>(),
+            vec![(1.25, true), (2.0, true), (1.5, false)]
+        );
+        assert!(parse_duration("1h@2x").is_err());
     }
 
     #[test]
     fn test_run() {
         let config = TimeConfig::new("1d", "2x").unwrap();
         let result = config.trim().unwrap();
-        assert_eq!(parse_time(result.0), "12h");
-        assert_eq!(parse_time(result.1), "12h");
-        assert_eq!(result.2, 1);
+        assert_eq!(parse_time(result.duration), "12h");
+        assert_eq!(parse_time(result.saved), "12h");
+        assert_eq!(result.splits, 1);
+    }
+
+    fn at(date_time: &str) -> DateTime<chrono_tz::Tz> {
+        let naive = NaiveDateTime::parse_from_str(date_time, "%Y-%m-%d %H:%M").unwrap();
+        resolve_local(&chrono_tz::UTC, naive)
+    }
+
+    fn berlin(date_time: &str) -> DateTime<chrono_tz::Tz> {
+        let naive = NaiveDateTime::parse_from_str(date_time, "%Y-%m-%d %H:%M").unwrap();
+        resolve_local(&chrono_tz::Europe::Berlin, naive)
+    }
+
+    #[test]
+    fn test_parse_clock_time() {
+        assert_eq!(
+            parse_clock_time("22:00").unwrap(),
+            NaiveTime::from_hms_opt(22, 0, 0).unwrap()
+        );
+        assert_eq!(
+            parse_clock_time("01:30:15").unwrap(),
+            NaiveTime::from_hms_opt(1, 30, 15).unwrap()
+        );
+        assert!(parse_clock_time("25:00").is_err());
+        assert!(parse_clock_time("10pm").is_err());
+    }
+
+    #[test]
+    fn test_day_bounds_default() {
+        let bounds = DayBounds::default();
+
+        assert_eq!(
+            bounds.time_left(&at("2026-03-01 20:00")),
+            Duration::from_secs(4 * 3600 - 1)
+        );
+    }
+
+    #[test]
+    fn test_day_bounds_end_only() {
+        let bounds = DayBounds {
+            start: None,
+            end: parse_clock_time("01:00").unwrap(),
+        };
+
+        assert_eq!(
+            bounds.time_left(&at("2026-03-01 23:00")),
+            Duration::from_secs(2 * 3600)
+        );
+        assert_eq!(
+            bounds.time_left(&at("2026-03-02 00:30")),
+            Duration::from_secs(1800)
+        );
+    }
+
+    #[test]
+    fn test_day_bounds_window() {
+        let bounds = DayBounds {
+            start: Some(parse_clock_time("07:00").unwrap()),
+            end: parse_clock_time("22:00").unwrap(),
+        };
+
+        assert_eq!(
+            bounds.time_left(&at("2026-03-01 20:00")),
+            Duration::from_secs(2 * 3600)
+        );
+        // before the day starts, the whole day is available
+        assert_eq!(
+            bounds.time_left(&at("2026-03-01 05:00")),
+            Duration::from_secs(15 * 3600)
+        );
+        assert_eq!(
+            bounds.time_left(&at("2026-03-01 23:00")),
+            Duration::from_secs(15 * 3600)
+        );
+    }
+
+    #[test]
+    fn test_day_bounds_window_across_midnight() {
+        let bounds = DayBounds {
+            start: Some(parse_clock_time("07:00").unwrap()),
+            end: parse_clock_time("01:00").unwrap(),
+        };
+
+        assert_eq!(
+            bounds.time_left(&at("2026-03-01 23:00")),
+            Duration::from_secs(2 * 3600)
+        );
+        assert_eq!(
+            bounds.time_left(&at("2026-03-02 00:30")),
+            Duration::from_secs(1800)
+        );
+        assert_eq!(
+            bounds.time_left(&at("2026-03-02 03:00")),
+            Duration::from_secs(18 * 3600)
+        );
+        assert_eq!(
+            bounds.time_left(&at("2026-03-02 09:00")),
+            Duration::from_secs(16 * 3600)
+        );
+    }
+
+    #[test]
+    fn test_day_bounds_dst() {
+        let bounds = DayBounds::default();
+
+        // clocks go forward at 02:00 on 2026-03-29, so the day is an hour shorter
+        assert_eq!(
+            bounds.time_left(&berlin("2026-03-29 00:00")),
+            Duration::from_secs(23 * 3600 - 1)
+        );
+        // clocks go back at 03:00 on 2026-10-25, so the day is an hour longer
+        assert_eq!(
+            bounds.time_left(&berlin("2026-10-25 00:00")),
+            Duration::from_secs(25 * 3600 - 1)
+        );
+
+        // 02:30 does not exist on 2026-03-29 and resolves to 03:00
+        let gap = DayBounds {
+            start: None,
+            end: parse_clock_time("02:30").unwrap(),
+        };
+        assert_eq!(
+            gap.time_left(&berlin("2026-03-29 00:00")),
+            Duration::from_secs(2 * 3600)
+        );
+    }
+
+    #[test]
+    fn test_day_context() {
+        let now = "2026-03-01T20:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let bounds = DayBounds {
+            start: None,
+            end: parse_clock_time("22:00").unwrap(),
+        };
+
+        let utc = DayContext::new(bounds, Zone::Named(chrono_tz::UTC)).at(now);
+        assert_eq!(utc.time_left(), Duration::from_secs(2 * 3600));
+        assert_eq!(
+            time_in_day_after(Duration::from_secs(1800), &utc),
+            Duration::from_secs(5400)
+        );
+        assert_eq!(
+            time_in_day_after(Duration::from_secs(3 * 3600), &utc),
+            Duration::ZERO
+        );
+
+        // 20:00 UTC is already 02:00 the next day in Dhaka
+        let dhaka =
+            DayContext::new(bounds, Zone::Named(parse_timezone("Asia/Dhaka").unwrap())).at(now);
+        assert_eq!(dhaka.time_left(), Duration::from_secs(20 * 3600));
+
+        assert!(parse_timezone("Mars/Olympus_Mons").is_err());
+    }
+
+    #[test]
+    fn test_finish_time() {
+        let now = "2026-03-01T22:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let day = DayContext::new(DayBounds::default(), Zone::Named(chrono_tz::UTC)).at(now);
+
+        let finish = day.finish_time(Duration::from_secs(1800)).unwrap();
+        assert_eq!(finish.to_string(), "22:30");
+        assert_eq!(finish.day_offset, 0);
+
+        let finish = day.finish_time(Duration::from_secs(3 * 3600)).unwrap();
+        assert_eq!(finish.to_string(), "01:00 (+1d)");
+
+        let finish = day
+            .finish_time(Duration::from_secs(2 * 86400 + 3600))
+            .unwrap();
+        assert_eq!(finish.to_string(), "23:00 (+2d)");
+    }
+
+    #[test]
+    fn test_finish_time_start_at() {
+        let now = "2026-03-01T20:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let day = DayContext::new(DayBounds::default(), Zone::Named(chrono_tz::UTC)).at(now);
+
+        let planned = day.with_start_at(Some(parse_clock_time("21:00").unwrap()));
+        assert_eq!(
+            planned
+                .finish_time(Duration::from_secs(5400))
+                .unwrap()
+                .to_string(),
+            "22:30"
+        );
+        assert_eq!(planned.time_left(), Duration::from_secs(3 * 3600 - 1));
+
+        // a start time earlier than now is taken as tomorrow's
+        let tomorrow = day.with_start_at(Some(parse_clock_time("09:00").unwrap()));
+        assert_eq!(
+            tomorrow
+                .finish_time(Duration::from_secs(3600))
+                .unwrap()
+                .to_string(),
+            "10:00 (+1d)"
+        );
+    }
+
+    #[test]
+    fn test_required_multiplier() {
+        assert_eq!(
+            required_multiplier(
+                Duration::from_secs(3 * 3600),
+                Duration::from_secs(2 * 3600),
+                None
+            ),
+            Some(1.5)
+        );
+        assert_eq!(
+            required_multiplier(Duration::from_secs(3600), Duration::ZERO, None),
+            None
+        );
+
+        // two hours of watching and four pomodoro breaks fill 2h20m
+        let required = required_multiplier(
+            Duration::from_secs(3 * 3600),
+            Duration::from_secs(2 * 3600 + 20 * 60),
+            Some(BreakPolicy::POMODORO),
+        )
+        .unwrap();
+        assert_eq!(required, 1.5);
+
+        let required = required_multiplier(
+            Duration::from_secs(8 * 3600),
+            Duration::from_secs(7 * 3600 + 50 * 60),
+            Some(BreakPolicy::POMODORO),
+        )
+        .unwrap();
+        let watch = Duration::from_secs(8 * 3600)
+            .checked_div_f64(suggested_multiplier(required, 0.05))
+            .unwrap();
+        assert!(
+            BreakPolicy::POMODORO.session_length(watch) <= Duration::from_secs(7 * 3600 + 50 * 60)
+        );
+
+        assert_eq!(round_up_to_step(1.5, 0.05), 1.5);
+        assert_eq!(round_up_to_step(1.43, 0.05), 1.45);
+        assert_eq!(round_up_to_step(1.43, 0.25), 1.5);
+        assert_eq!(round_up_to_step(1.2, 0.05), 1.2);
+        assert_eq!(round_up_to_step(0.61, 0.25), 0.75);
+
+        // already fits, so no slowing down
+        assert_eq!(suggested_multiplier(0.5, 0.05), 1.0);
+        assert_eq!(suggested_multiplier(0.55, 0.25), 1.0);
+        assert_eq!(suggested_multiplier(1.0, 0.05), 1.0);
+        assert_eq!(suggested_multiplier(1.01, 0.05), 1.05);
+    }
+
+    #[test]
+    fn test_breaks() {
+        let pomodoro = BreakPolicy::POMODORO;
+
+        assert_eq!(pomodoro.breaks_for(Duration::ZERO), Duration::ZERO);
+        assert_eq!(
+            pomodoro.breaks_for(Duration::from_secs(25 * 60)),
+            Duration::ZERO
+        );
+        assert_eq!(
+            pomodoro.breaks_for(Duration::from_secs(50 * 60)),
+            Duration::from_secs(5 * 60)
+        );
+        assert_eq!(
+            pomodoro.session_length(Duration::from_secs(2 * 3600)),
+            Duration::from_secs(2 * 3600 + 4 * 5 * 60)
+        );
+
+        assert_eq!(
+            pomodoro.watch_time_within(Duration::from_secs(25 * 60)),
+            Duration::from_secs(25 * 60)
+        );
+        // the break after the first block leaves 25 minutes for the second
+        assert_eq!(
+            pomodoro.watch_time_within(Duration::from_secs(55 * 60)),
+            Duration::from_secs(50 * 60)
+        );
+        assert_eq!(
+            pomodoro.watch_time_within(Duration::from_secs(28 * 60)),
+            Duration::from_secs(25 * 60)
+        );
+        assert_eq!(
+            pomodoro.watch_time_within(Duration::from_secs(2 * 3600 + 20 * 60)),
+            Duration::from_secs(2 * 3600)
+        );
+        assert_eq!(pomodoro.watch_time_within(Duration::ZERO), Duration::ZERO);
+
+        let result = TimeConfig::new("4h", "2x")
+            .unwrap()
+            .with_breaks(Some(BreakPolicy {
+                every: Duration::from_secs(50 * 60),
+                length: Duration::from_secs(10 * 60),
+            }))
+            .trim()
+            .unwrap();
+        assert_eq!(result.duration, Duration::from_secs(2 * 3600));
+        assert_eq!(result.session, Duration::from_secs(2 * 3600 + 2 * 10 * 60));
     }
 }
@@ -1,8 +1,23 @@
-use std::path::PathBuf;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 use anyhow::{Result, bail};
 use arboard::Clipboard;
 
+use crate::{
+    core::{
+        api::ApiClientManager,
+        format::TimeFormat,
+        sources::Tally,
+        style::Style,
+        time::parse_duration,
+        youtils::{find_youtube_links, is_youtube_link},
+    },
+    errors::Diagnostic,
+};
+
 pub fn choose_or_grab_link(link: Option<String>, clip: bool) -> Result<String> {
     let link = if let Some(l) = link {
         l
@@ -22,6 +37,151 @@ pub fn choose_or_grab_link(link: Option<String>, clip: bool) -> Result<String> {
     Ok(link)
 }
 
+/// Like [`choose_or_grab_link`], for commands taking several links (or other sources).
+///
+/// A `-` among the links is replaced by the sources read from stdin, and those in `from_file` are added after
+/// the rest, one per line (see [`parse_sources`]). The clipboard is only used if no source was passed in any of
+/// these ways, and every YouTube link in it (see [`find_youtube_links`]) is listed on stderr. With `first`,
+/// only the first source on stdin or in the clipboard is used.
+pub fn choose_or_grab_links(
+    links: Vec<String>,
+    from_file: Option<&Path>,
+    clip: bool,
+    first: bool,
+) -> Result<Vec<String>> {
+    let mut sources = Vec::new();
+    let mut read_any = false;
+
+    for link in links {
+        if link == "-" {
+            let text = std::io::read_to_string(std::io::stdin())
+                .map_err(|e| anyhow::anyhow!("Failed to read from stdin: {e}"))?;
+            let mut read = parse_sources(&text, "stdin")?;
+            if first {
+                read.truncate(1);
+            }
+            sources.extend(read);
+            read_any = true;
+        } else {
+            sources.push(link);
+        }
+    }
+
+    if let Some(path) = from_file {
+        let text = fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", path.display()))?;
+        sources.extend(parse_sources(&text, &path.display().to_string())?);
+        read_any = true;
+    }
+
+    if sources.is_empty() {
+        if read_any {
+            bail!("No links or durations were found in the given input.")
+        }
+
+        let text = choose_or_grab_link(None, clip)?;
+        sources = find_youtube_links(&text);
+        if first {
+            sources.truncate(1);
+        }
+
+        if sources.is_empty() {
+            bail!("No YouTube links were found in the clipboard.")
+        }
+
+        eprintln!("Detected {} link(s) in the clipboard:", sources.len());
+        for source in &sources {
+            eprintln!("  {source}");
+        }
+    }
+
+    Ok(sources)
+}
+
+/// Reads one source per line as in [`read_sources`], failing on the first line that is neither a YouTube link
+/// nor a duration. Bare IDs and handles are only accepted as arguments, since ordinary words could pass for
+/// them. `origin` names where the text came from, e.g. `stdin` or a file path.
+pub fn parse_sources(text: &str, origin: &str) -> Result<Vec<String>> {
+    let sources = read_sources(text);
+
+    for source in &sources {
+        // lines starting with a number were most likely meant as durations
+        match parse_duration(source) {
+            Ok(_) => {}
+            _ if is_youtube_link(source) => {}
+            Err(e) if source.starts_with(|c: char| c.is_ascii_digit()) => {
+                bail!("Not a valid YouTube URL or duration in {origin}: {e}")
+            }
+            Err(_) => {
+                let diagnostic = Diagnostic::new(source, 0..source.len())
+                    .with_expected(["a YouTube link", "a duration"]);
+                bail!("Not a valid YouTube URL or duration in {origin}:{diagnostic}")
+            }
+        }
+    }
+
+    Ok(sources)
+}
+
+/// Reads one source per line, skipping blank lines and comments.
+///
+/// Comments start with a `#` at the beginning of a line or after whitespace, so a `#t=` in a link is kept.
+#[must_use]
+pub fn read_sources(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| {
+            let comment = line
+                .char_indices()
+                .find(|(i, c)| {
+                    *c == '#'
+                        && line[..*i]
+                            .chars()
+                            .next_back()
+                            .is_none_or(char::is_whitespace)
+                })
+                .map_or(line.len(), |(i, _)| i);
+
+            line[..comment].trim()
+        })
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Prints what each source accounts for, if there is more than one.
+pub fn print_subtotals(tally: &Tally, format: &TimeFormat) {
+    if tally.subtotals.len() < 2 {
+        return;
+    }
+
+    println!("\nSources:");
+    for (i, s) in tally.subtotals.iter().enumerate() {
+        let mut details = vec![format!("{} item(s)", s.items)];
+        if let Some(start) = s.start {
+            details.push(format!("from {} in", format.format(start)));
+        }
+        if s.duplicates > 0 {
+            details.push(format!("{} already counted", s.duplicates));
+        }
+
+        println!(
+            "  {}. {}: {} ({})",
+            i + 1,
+            s.label,
+            format.format(s.duration),
+            details.join(", ")
+        );
+    }
+    println!("Total: {}", format.format(tally.total()));
+}
+
+/// Prints the warnings collected by the manager's requests to stderr.
+pub fn print_warnings(manager: &ApiClientManager, style: &Style) {
+    for warning in manager.take_warnings() {
+        eprintln!("{}Warning: {warning}{}", style.boldred(), style.reset());
+    }
+}
+
 pub fn get_config_path() -> Result<PathBuf> {
     let home = dirs::home_dir();
 
@@ -31,3 +191,57 @@ pub fn get_config_path() -> Result<PathBuf> {
         bail!("Could not determine HOME directory.")
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_sources() {
+        let text = "# week 1\n\
+            https://youtu.be/ZNYRjxJ3sdY#t=1m  # resume here\n\
+            \n\
+            \tPLHXZ9OQGMqxersk8fUxiUMSIx0DBqsKZS\n\
+            45m # reading\r\n\
+            #done";
+
+        assert_eq!(
+            read_sources(text),
+            vec![
+                "https://youtu.be/ZNYRjxJ3sdY#t=1m",
+                "PLHXZ9OQGMqxersk8fUxiUMSIx0DBqsKZS",
+                "45m",
+            ]
+        );
+        assert!(read_sources("\n  # nothing here\n").is_empty());
+    }
+
+    #[test]
+    fn test_parse_sources() {
+        assert_eq!(
+            parse_sources("45m # reading\nhttps://youtu.be/sEWIDdQKWgc\n", "stdin").unwrap(),
+            vec!["45m", "https://youtu.be/sEWIDdQKWgc"]
+        );
+
+        // bare IDs are only taken as arguments
+        assert!(parse_sources("ZNYRjxJ3sdY\n", "stdin").is_err());
+
+        let error = parse_sources("45m\n30 mns\n", "stdin")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.starts_with(
+                "Not a valid YouTube URL or duration in stdin: unknown time unit `mns`"
+            )
+        );
+        assert!(error.contains("\n  30 mns\n     ^^^"));
+
+        let error = parse_sources("Assignments\n", "list.txt")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains(
+            "in list.txt:\n  Assignments\n  ^^^^^^^^^^^ expected a YouTube link or a duration"
+        ));
+    }
+}
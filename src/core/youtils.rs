@@ -1,9 +1,14 @@
 use anyhow::{Result as YoutilsResult, bail};
+use std::{fmt::Display, ops::Range, str::FromStr};
+
+use chrono::{DateTime, NaiveDate};
 use reqwest::Url;
 
-use crate::core::config::Config;
+use crate::{
+    core::{config::Config, duration::Duration, time::parse_duration},
+    errors::TYoutubeError,
+};
 
-#[must_use]
 pub fn get_youtube_api_key() -> YoutilsResult<String> {
     const ENV_VAR_NAME: &str = "TRIMSEC_YOUTUBE_KEY";
     let x = std::env::var(ENV_VAR_NAME).ok();
@@ -21,11 +26,8 @@ pub fn get_youtube_api_key() -> YoutilsResult<String> {
                     ),
                 },
                 Err(e) => {
-                    match e {
-                        crate::errors::TConfigError::ParseFailed(p) => {
-                            bail!("Failed to parse .trimsecrc file at path: {p:?}")
-                        }
-                        _ => {}
+                    if let crate::errors::TConfigError::ParseFailed(p) = e {
+                        bail!("Failed to parse .trimsecrc file at path: {p:?}")
                     }
                     bail!(
                         "Missing {ENV_VAR_NAME} environment variable or .trimsecrc file in $HOME; read README.md to learn more."
@@ -36,73 +38,401 @@ pub fn get_youtube_api_key() -> YoutilsResult<String> {
     }
 }
 
-#[derive(Debug, PartialEq)]
-pub struct YoutubeId {
-    pub id: String,
-    pub is_playlist: bool,
+/// A channel, as referenced in a URL.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChannelRef {
+    /// A channel ID, e.g. `UCsBjURrPoezykLs9EqgamOA`.
+    Id(String),
+    /// A handle without its leading `@`.
+    Handle(String),
+    /// A legacy username, as in `/user/<name>` URLs.
+    Username(String),
 }
 
-#[must_use]
-pub fn get_youtube_id(link: &str) -> Option<YoutubeId> {
-    let mut is_playlist = false;
+impl Display for ChannelRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Id(id) => write!(f, "{id}"),
+            Self::Handle(handle) => write!(f, "@{handle}"),
+            Self::Username(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+/// The content a YouTube link points to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum YoutubeId {
+    /// A single video, with the position a link starts playing it at (e.g. `t=30m34s`), if any.
+    Video { id: String, start: Option<Duration> },
+    /// A playlist, with the position a link starts playing it at (e.g. `index=37`), if any.
+    Playlist { id: String, index: Option<usize> },
+    /// All uploads of a channel.
+    Channel(ChannelRef),
+}
+
+impl YoutubeId {
+    /// Whether the ID stands for a list of videos (a playlist or a channel's uploads) rather than a single one.
+    #[must_use]
+    pub fn is_collection(&self) -> bool {
+        !matches!(self, Self::Video { .. })
+    }
+
+    /// The position a video link starts playing at, which is left out of its remaining duration.
+    #[must_use]
+    pub fn start(&self) -> Option<Duration> {
+        match self {
+            Self::Video { start, .. } => *start,
+            _ => None,
+        }
+    }
+
+    /// The position in a playlist a link starts playing at.
+    #[must_use]
+    pub fn index(&self) -> Option<usize> {
+        match self {
+            Self::Playlist { index, .. } => *index,
+            _ => None,
+        }
+    }
+
+    /// Drops the timestamp of a video link, so the video is counted from its beginning.
+    #[must_use]
+    pub fn without_start(self) -> Self {
+        match self {
+            Self::Video { id, .. } => Self::Video { id, start: None },
+            other => other,
+        }
+    }
+}
+
+/// Parses the value of a `t=`/`start=` timestamp, given either in seconds (`1834`, `1834s`) or in units
+/// (`30m34s`, `1h2m3s`).
+fn parse_timestamp(value: &str) -> Option<Duration> {
+    if value.is_empty() || !value.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return None;
+    }
+
+    if value.chars().all(|c| c.is_ascii_digit()) {
+        return value.parse().ok().map(Duration::from_secs);
+    }
 
-    if let Ok(parsed_url) = Url::parse(link) {
-        if !parsed_url.host_str().is_some_and(|f| {
-            f == "www.youtube.com"
-                || f == "youtube.com"
-                || f == "youtu.be"
-                || f == "music.youtube.com"
-        }) {
-            return None;
+    parse_duration(value).ok().map(|(duration, _)| duration)
+}
+
+/// Limits the videos taken from a playlist or channel to those published within a range of dates.
+///
+/// Both ends are inclusive and compared against the publishing date in UTC.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PublishedRange {
+    pub since: Option<NaiveDate>,
+    pub until: Option<NaiveDate>,
+}
+
+impl PublishedRange {
+    /// Whether a video published at the given RFC 3339 timestamp lies within the range.
+    ///
+    /// Videos without a (valid) publishing date, such as private ones, only pass an unbounded range.
+    #[must_use]
+    pub fn contains(&self, published_at: Option<&str>) -> bool {
+        if self.since.is_none() && self.until.is_none() {
+            return true;
         }
 
-        let query_search = |q: &str, ptr: &str| {
-            q.split('&')
-                .find(|p| p.starts_with(ptr))
-                .map(|p| p.trim_start_matches(ptr).to_string())
+        let Some(date) = published_at
+            .and_then(|p| DateTime::parse_from_rfc3339(p).ok())
+            .map(|p| p.to_utc().date_naive())
+        else {
+            return false;
         };
 
-        let id: Option<String> = if parsed_url.path().starts_with("/shorts/")
-            || parsed_url.path().starts_with("/embed/")
-        {
-            parsed_url
-                .path_segments()
-                .and_then(|mut f| f.next_back())
-                .map(|s| s.to_string())
-        } else if parsed_url.path().starts_with("/watch") {
-            parsed_url.query().and_then(|q| {
-                if q.contains("list=") {
-                    let listsearch = query_search(q, "list=");
-
-                    if listsearch.is_none() || listsearch.clone().is_some_and(|f| f.is_empty()) {
-                        query_search(q, "v=")
-                    } else {
-                        is_playlist = true;
-                        listsearch
-                    }
-                } else {
-                    query_search(q, "v=")
-                }
-            })
-        } else if parsed_url.path().starts_with("/playlist") {
-            is_playlist = true;
-            parsed_url.query().and_then(|q| query_search(q, "list="))
-        } else if parsed_url.host_str().is_some_and(|f| f == "youtu.be") {
-            Some(parsed_url.path().trim_start_matches("/").to_string())
-        } else {
-            None
+        self.since.is_none_or(|since| date >= since) && self.until.is_none_or(|until| date <= until)
+    }
+}
+
+/// A range of positions in a playlist, counted from 1 and inclusive on both ends (e.g. `37..60`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ItemRange {
+    /// The first position to take, or the start of the playlist.
+    pub first: Option<usize>,
+    /// The last position to take, or the end of the playlist.
+    pub last: Option<usize>,
+}
+
+impl ItemRange {
+    /// Resolves the positions to take from a playlist. An explicit `range` takes precedence over a `from`
+    /// position, which in turn takes precedence over the `index=` of the link. `max_items` (0 for no limit)
+    /// then caps the number of items taken.
+    #[must_use]
+    pub fn resolve(
+        range: Option<Self>,
+        from: Option<usize>,
+        id: &YoutubeId,
+        max_items: usize,
+    ) -> Self {
+        let range = range.unwrap_or(Self {
+            first: from.or(id.index()),
+            last: None,
+        });
+
+        if max_items == 0 {
+            return range;
+        }
+
+        let cap = range.first.unwrap_or(1).saturating_add(max_items - 1);
+        Self {
+            last: Some(range.last.map_or(cap, |last| last.min(cap))),
+            ..range
+        }
+    }
+
+    /// Clamps the range to a playlist of `len` items, returning the (0-based) indices to take and whether
+    /// the range reached past the end of the playlist.
+    #[must_use]
+    pub fn clamp(&self, len: usize) -> (Range<usize>, bool) {
+        let start = self.first.unwrap_or(1).saturating_sub(1);
+        let end = self.last.unwrap_or(len);
+        let clamped = end > len || (self.first.is_some() && start >= len);
+        let end = end.min(len);
+
+        (start.min(end)..end, clamped)
+    }
+}
+
+impl Display for ItemRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(first) = self.first {
+            write!(f, "{first}")?;
+        }
+        write!(f, "..")?;
+        if let Some(last) = self.last {
+            write!(f, "{last}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ItemRange {
+    type Err = TYoutubeError;
+
+    /// Parses a range such as `37..60`, `37..` or `..60`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || TYoutubeError::InvalidRange(s.to_string());
+
+        let (first, last) = s.split_once("..").ok_or_else(invalid)?;
+        let position = |p: &str| {
+            let p = p.trim();
+
+            if p.is_empty() {
+                Ok(None)
+            } else {
+                p.parse::<usize>()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .map(Some)
+                    .ok_or_else(invalid)
+            }
         };
 
-        if id == Some("".to_string()) {
-            None
-        } else {
-            id.map(|id| YoutubeId { id, is_playlist })
+        let range = Self {
+            first: position(first)?,
+            last: position(last.trim_start_matches('='))?,
+        };
+
+        if let (Some(first), Some(last)) = (range.first, range.last)
+            && first > last
+        {
+            return Err(invalid());
         }
+
+        Ok(range)
+    }
+}
+
+/// Hosts serving YouTube videos and playlists.
+const HOSTS: &[&str] = &[
+    "www.youtube.com",
+    "youtube.com",
+    "m.youtube.com",
+    "music.youtube.com",
+    "youtu.be",
+    "www.youtube-nocookie.com",
+    "youtube-nocookie.com",
+];
+
+/// Paths which are directly followed by a video ID, e.g. `/live/<id>`.
+const VIDEO_PATHS: &[&str] = &["/shorts/", "/embed/", "/live/", "/v/"];
+
+/// Prefixes of playlist IDs that may be passed without a URL.
+const PLAYLIST_PREFIXES: &[&str] = &["PL", "UU", "FL", "LL", "OL"];
+
+fn is_id_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_'
+}
+
+/// Whether the input is a bare video ID, which is always 11 characters long.
+fn is_video_id(input: &str) -> bool {
+    input.len() == 11 && input.chars().all(is_id_char)
+}
+
+/// Whether the input is a bare playlist ID, e.g. `PLHXZ9OQGMqxersk8fUxiUMSIx0DBqsKZS`.
+fn is_playlist_id(input: &str) -> bool {
+    input.len() > 11
+        && PLAYLIST_PREFIXES.iter().any(|p| input.starts_with(p))
+        && input.chars().all(is_id_char)
+}
+
+/// Whether the input is a bare channel ID, which is `UC` followed by 22 characters.
+fn is_channel_id(input: &str) -> bool {
+    input.len() == 24 && input.starts_with("UC") && input.chars().all(is_id_char)
+}
+
+/// Returns the handle in a bare `@handle`, without the `@`.
+fn bare_handle(input: &str) -> Option<&str> {
+    input
+        .strip_prefix('@')
+        .filter(|h| !h.is_empty() && h.chars().all(|c| is_id_char(c) || c == '.'))
+}
+
+/// Extracts what a YouTube URL points to: a video, a playlist or a channel (`/@handle`, `/channel/<id>` or
+/// `/user/<name>`). Bare video IDs, playlist IDs, channel IDs and `@handle`s are accepted as well.
+///
+/// URLs may omit the scheme (e.g. `youtu.be/<id>`). Note that a short duration such as `3h-20m-5m1s` could also
+/// pass for a bare video ID, so callers accepting both should try parsing a duration first.
+#[must_use]
+pub fn get_youtube_id(link: &str) -> Option<YoutubeId> {
+    let link = link.trim();
+
+    if let Some(handle) = bare_handle(link) {
+        return Some(YoutubeId::Channel(ChannelRef::Handle(handle.to_string())));
+    } else if is_channel_id(link) {
+        return Some(YoutubeId::Channel(ChannelRef::Id(link.to_string())));
+    } else if is_playlist_id(link) {
+        return Some(YoutubeId::Playlist {
+            id: link.to_string(),
+            index: None,
+        });
+    } else if is_video_id(link) {
+        return Some(YoutubeId::Video {
+            id: link.to_string(),
+            start: None,
+        });
+    }
+
+    let mut is_playlist = false;
+
+    let parsed_url = Url::parse(link)
+        .or_else(|_| Url::parse(&format!("https://{link}")))
+        .ok()?;
+
+    if !parsed_url.host_str().is_some_and(|f| HOSTS.contains(&f)) {
+        return None;
+    }
+
+    let query_search = |q: &str, ptr: &str| {
+        q.split('&')
+            .find(|p| p.starts_with(ptr))
+            .map(|p| p.trim_start_matches(ptr).to_string())
+    };
+
+    let path = parsed_url.path();
+
+    // channel pages may have a tab appended, e.g. `/@handle/videos`
+    let channel_segment = |prefix: &str| {
+        path.strip_prefix(prefix)
+            .and_then(|rest| rest.split('/').next())
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+
+    if let Some(handle) = channel_segment("/@") {
+        return Some(YoutubeId::Channel(ChannelRef::Handle(handle)));
+    } else if let Some(id) = channel_segment("/channel/") {
+        return Some(YoutubeId::Channel(ChannelRef::Id(id)));
+    } else if let Some(name) = channel_segment("/user/") {
+        return Some(YoutubeId::Channel(ChannelRef::Username(name)));
+    }
+
+    let id: Option<String> = if path == "/embed/videoseries" {
+        // embedded playlists
+        is_playlist = true;
+        parsed_url.query().and_then(|q| query_search(q, "list="))
+    } else if VIDEO_PATHS.iter().any(|p| path.starts_with(p)) {
+        parsed_url
+            .path_segments()
+            .and_then(|mut f| f.next_back())
+            .map(|s| s.to_string())
+    } else if path.starts_with("/watch") {
+        parsed_url.query().and_then(|q| {
+            if q.contains("list=") {
+                let listsearch = query_search(q, "list=");
+
+                if listsearch.is_none() || listsearch.clone().is_some_and(|f| f.is_empty()) {
+                    query_search(q, "v=")
+                } else {
+                    is_playlist = true;
+                    listsearch
+                }
+            } else {
+                query_search(q, "v=")
+            }
+        })
+    } else if path.starts_with("/playlist") {
+        is_playlist = true;
+        parsed_url.query().and_then(|q| query_search(q, "list="))
+    } else if parsed_url.host_str().is_some_and(|f| f == "youtu.be") {
+        Some(path.trim_start_matches("/").to_string())
     } else {
         None
+    };
+
+    // `t=` is used by share links and `#t=` by older ones, while embeds use `start=`
+    let start = parsed_url
+        .query()
+        .into_iter()
+        .chain(parsed_url.fragment())
+        .flat_map(|q| [query_search(q, "t="), query_search(q, "start=")])
+        .flatten()
+        .find_map(|t| parse_timestamp(&t));
+    let index = parsed_url
+        .query()
+        .and_then(|q| query_search(q, "index="))
+        .and_then(|i| i.parse::<usize>().ok())
+        .filter(|i| *i > 0);
+
+    if id == Some("".to_string()) {
+        None
+    } else {
+        id.map(|id| {
+            if is_playlist {
+                YoutubeId::Playlist { id, index }
+            } else {
+                YoutubeId::Video { id, start }
+            }
+        })
     }
 }
 
+/// Whether the given string is a link to something on YouTube, rather than e.g. a bare ID or handle, which
+/// ordinary words could pass for.
+#[must_use]
+pub fn is_youtube_link(link: &str) -> bool {
+    link.contains('/') && get_youtube_id(link).is_some()
+}
+
+/// Finds all YouTube links in a piece of text, such as a chat message or a syllabus, in order of appearance.
+///
+/// Bare IDs and handles are not picked up from running text (see [`is_youtube_link`]).
+#[must_use]
+pub fn find_youtube_links(text: &str) -> Vec<String> {
+    text.split(|c: char| {
+        c.is_whitespace() || matches!(c, '<' | '>' | '"' | '\'' | '(' | ')' | '[' | ']' | '|')
+    })
+    .map(|t| t.trim_end_matches(['.', ',', ';', ':', '!', '?']))
+    .filter(|t| is_youtube_link(t))
+    .map(str::to_string)
+    .collect()
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
@@ -113,32 +443,32 @@ mod tests {
         assert_eq!(get_youtube_id("test-driven-development"), None);
         assert_eq!(
             get_youtube_id("https://www.youtube.com/embed/ZNYRjxJ3sdY"),
-            Some(YoutubeId {
+            Some(YoutubeId::Video {
                 id: "ZNYRjxJ3sdY".to_string(),
-                is_playlist: false
+                start: None
             })
         );
         assert_eq!(
             get_youtube_id("https://www.youtube.com/shorts/ZNYRjxJ3sdY"),
-            Some(YoutubeId {
+            Some(YoutubeId::Video {
                 id: "ZNYRjxJ3sdY".to_string(),
-                is_playlist: false
+                start: None
             })
         );
         assert_eq!(
             get_youtube_id("https://www.youtube.com/watch?v=ZNYRjxJ3sdY"),
-            Some(YoutubeId {
+            Some(YoutubeId::Video {
                 id: "ZNYRjxJ3sdY".to_string(),
-                is_playlist: false
+                start: None
             })
         );
         assert_eq!(
             get_youtube_id(
                 "https://www.youtube.com/playlist?list=PLHXZ9OQGMqxersk8fUxiUMSIx0DBqsKZS"
             ),
-            Some(YoutubeId {
+            Some(YoutubeId::Playlist {
                 id: "PLHXZ9OQGMqxersk8fUxiUMSIx0DBqsKZS".to_string(),
-                is_playlist: true
+                index: None
             })
         );
         assert_eq!(
@@ -150,23 +480,23 @@ mod tests {
             get_youtube_id(
                 "https://www.youtube.com/watch?v=rdXw7Ps9vxc&list=PLHXZ9OQGMqxersk8fUxiUMSIx0DBqsKZS"
             ),
-            Some(YoutubeId {
+            Some(YoutubeId::Playlist {
                 id: "PLHXZ9OQGMqxersk8fUxiUMSIx0DBqsKZS".to_string(),
-                is_playlist: true
+                index: None
             })
         );
         assert_eq!(
             get_youtube_id("https://www.youtube.com/watch?v=rdXw7Ps9vxc&list="),
-            Some(YoutubeId {
+            Some(YoutubeId::Video {
                 id: "rdXw7Ps9vxc".to_string(),
-                is_playlist: false
+                start: None
             })
         );
         assert_eq!(
             get_youtube_id("https://youtu.be/sEWIDdQKWgc?si=Ywu5MycwAaZ4cZ3t"),
-            Some(YoutubeId {
+            Some(YoutubeId::Video {
                 id: "sEWIDdQKWgc".to_string(),
-                is_playlist: false
+                start: None
             })
         );
         assert_eq!(
@@ -179,4 +509,300 @@ mod tests {
             None,
         )
     }
+
+    #[test]
+    fn test_url_forms() {
+        const VIDEO: &str = "ZNYRjxJ3sdY";
+        const PLAYLIST: &str = "PLHXZ9OQGMqxersk8fUxiUMSIx0DBqsKZS";
+
+        let cases: &[(&str, Option<(&str, bool)>)] = &[
+            // hosts
+            (
+                "https://m.youtube.com/watch?v=ZNYRjxJ3sdY",
+                Some((VIDEO, false)),
+            ),
+            (
+                "https://music.youtube.com/watch?v=ZNYRjxJ3sdY",
+                Some((VIDEO, false)),
+            ),
+            (
+                "https://youtube.com/watch?v=ZNYRjxJ3sdY",
+                Some((VIDEO, false)),
+            ),
+            (
+                "https://www.youtube-nocookie.com/embed/ZNYRjxJ3sdY",
+                Some((VIDEO, false)),
+            ),
+            (
+                "https://youtube-nocookie.com/embed/ZNYRjxJ3sdY?rel=0",
+                Some((VIDEO, false)),
+            ),
+            ("https://www.youtube.co/watch?v=ZNYRjxJ3sdY", None),
+            ("https://example.com/embed/ZNYRjxJ3sdY", None),
+            // paths
+            (
+                "https://www.youtube.com/live/ZNYRjxJ3sdY",
+                Some((VIDEO, false)),
+            ),
+            (
+                "https://www.youtube.com/live/ZNYRjxJ3sdY?feature=share",
+                Some((VIDEO, false)),
+            ),
+            (
+                "https://www.youtube.com/v/ZNYRjxJ3sdY",
+                Some((VIDEO, false)),
+            ),
+            (
+                "https://m.youtube.com/shorts/ZNYRjxJ3sdY",
+                Some((VIDEO, false)),
+            ),
+            ("https://www.youtube.com/live/", None),
+            (
+                "https://www.youtube.com/embed/videoseries?list=PLHXZ9OQGMqxersk8fUxiUMSIx0DBqsKZS",
+                Some((PLAYLIST, true)),
+            ),
+            (
+                "https://m.youtube.com/playlist?list=PLHXZ9OQGMqxersk8fUxiUMSIx0DBqsKZS",
+                Some((PLAYLIST, true)),
+            ),
+            // without a scheme
+            ("youtu.be/ZNYRjxJ3sdY", Some((VIDEO, false))),
+            ("m.youtube.com/watch?v=ZNYRjxJ3sdY", Some((VIDEO, false))),
+            // bare IDs
+            ("ZNYRjxJ3sdY", Some((VIDEO, false))),
+            ("  ZNYRjxJ3sdY\n", Some((VIDEO, false))),
+            ("sEW-DdQ_Wgc", Some(("sEW-DdQ_Wgc", false))),
+            ("PLHXZ9OQGMqxersk8fUxiUMSIx0DBqsKZS", Some((PLAYLIST, true))),
+            ("ZNYRjxJ3sd", None),
+            ("ZNYRjxJ3sdY1", None),
+            ("ZNYRjxJ3sd!", None),
+            ("PL", None),
+            ("1h30m", None),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(
+                get_youtube_id(input),
+                expected.map(|(id, is_playlist)| if is_playlist {
+                    YoutubeId::Playlist {
+                        id: id.to_string(),
+                        index: None,
+                    }
+                } else {
+                    YoutubeId::Video {
+                        id: id.to_string(),
+                        start: None,
+                    }
+                }),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_timestamps() {
+        let video = |start: Option<u64>| {
+            Some(YoutubeId::Video {
+                id: "ZNYRjxJ3sdY".to_string(),
+                start: start.map(Duration::from_secs),
+            })
+        };
+
+        let cases = [
+            ("https://youtu.be/ZNYRjxJ3sdY?t=1834", video(Some(1834))),
+            (
+                "https://youtu.be/ZNYRjxJ3sdY?si=abc&t=1834s",
+                video(Some(1834)),
+            ),
+            (
+                "https://www.youtube.com/watch?v=ZNYRjxJ3sdY&t=30m34s",
+                video(Some(1834)),
+            ),
+            (
+                "https://www.youtube.com/watch?v=ZNYRjxJ3sdY#t=1h2m3s",
+                video(Some(3723)),
+            ),
+            (
+                "https://www.youtube.com/embed/ZNYRjxJ3sdY?start=90",
+                video(Some(90)),
+            ),
+            ("https://youtu.be/ZNYRjxJ3sdY?t=0", video(Some(0))),
+            ("https://youtu.be/ZNYRjxJ3sdY?t=", video(None)),
+            ("https://youtu.be/ZNYRjxJ3sdY?t=soon", video(None)),
+            ("https://youtu.be/ZNYRjxJ3sdY?t=1h-2m", video(None)),
+            (
+                "https://www.youtube.com/watch?v=ZNYRjxJ3sdY&feature=share",
+                video(None),
+            ),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(get_youtube_id(input), expected, "{input}");
+        }
+
+        let id = get_youtube_id("https://youtu.be/ZNYRjxJ3sdY?t=90").unwrap();
+        assert_eq!(id.start(), Some(Duration::from_secs(90)));
+        assert_eq!(id.without_start(), video(None).unwrap());
+        assert_eq!(
+            get_youtube_id(
+                "https://www.youtube.com/playlist?list=PLHXZ9OQGMqxersk8fUxiUMSIx0DBqsKZS&t=90"
+            )
+            .unwrap()
+            .start(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_playlist_index() {
+        assert_eq!(
+            get_youtube_id(
+                "https://www.youtube.com/watch?v=rdXw7Ps9vxc&list=PLHXZ9OQGMqxersk8fUxiUMSIx0DBqsKZS&index=37"
+            ),
+            Some(YoutubeId::Playlist {
+                id: "PLHXZ9OQGMqxersk8fUxiUMSIx0DBqsKZS".to_string(),
+                index: Some(37),
+            })
+        );
+        assert_eq!(
+            get_youtube_id(
+                "https://www.youtube.com/playlist?list=PLHXZ9OQGMqxersk8fUxiUMSIx0DBqsKZS&index=0"
+            )
+            .unwrap()
+            .index(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_item_range() {
+        let range = |first, last| ItemRange { first, last };
+        let playlist = YoutubeId::Playlist {
+            id: "PLHXZ9OQGMqxersk8fUxiUMSIx0DBqsKZS".to_string(),
+            index: Some(37),
+        };
+
+        assert_eq!(
+            "37..60".parse::<ItemRange>().unwrap(),
+            range(Some(37), Some(60))
+        );
+        assert_eq!(
+            "37..=60".parse::<ItemRange>().unwrap(),
+            range(Some(37), Some(60))
+        );
+        assert_eq!("37..".parse::<ItemRange>().unwrap(), range(Some(37), None));
+        assert_eq!("..60".parse::<ItemRange>().unwrap(), range(None, Some(60)));
+        assert!("60..37".parse::<ItemRange>().is_err());
+        assert!("0..5".parse::<ItemRange>().is_err());
+        assert!("37".parse::<ItemRange>().is_err());
+        assert_eq!(range(Some(37), None).to_string(), "37..");
+
+        // precedence: --range, then --from, then index=
+        assert_eq!(
+            ItemRange::resolve(None, None, &playlist, 0),
+            range(Some(37), None)
+        );
+        assert_eq!(
+            ItemRange::resolve(None, Some(5), &playlist, 10),
+            range(Some(5), Some(14))
+        );
+        assert_eq!(
+            ItemRange::resolve(Some(range(Some(2), Some(8))), Some(5), &playlist, 3),
+            range(Some(2), Some(4))
+        );
+        assert_eq!(
+            ItemRange::resolve(None, None, &playlist.clone().without_start(), 0).first,
+            Some(37)
+        );
+
+        assert_eq!(range(Some(37), Some(60)).clamp(100), (36..60, false));
+        assert_eq!(range(Some(37), Some(60)).clamp(50), (36..50, true));
+        assert_eq!(range(Some(60), None).clamp(50), (50..50, true));
+        assert_eq!(range(None, None).clamp(50), (0..50, false));
+        assert_eq!(range(None, None).clamp(0), (0..0, false));
+    }
+
+    #[test]
+    fn test_find_links() {
+        let text = "Lecture 1: https://youtu.be/ZNYRjxJ3sdY?t=90, and the rest of the course is at \
+            <https://www.youtube.com/playlist?list=PLHXZ9OQGMqxersk8fUxiUMSIx0DBqsKZS>.\n\
+            Optional: [talk](youtube.com/watch?v=rdXw7Ps9vxc) or https://example.com/watch?v=rdXw7Ps9vxc. \
+            Programming is fun! Ask @someone.";
+
+        assert_eq!(
+            find_youtube_links(text),
+            vec![
+                "https://youtu.be/ZNYRjxJ3sdY?t=90",
+                "https://www.youtube.com/playlist?list=PLHXZ9OQGMqxersk8fUxiUMSIx0DBqsKZS",
+                "youtube.com/watch?v=rdXw7Ps9vxc",
+            ]
+        );
+        assert!(find_youtube_links("nothing to see here").is_empty());
+    }
+
+    #[test]
+    fn test_channels() {
+        let handle = |h: &str| Some(YoutubeId::Channel(ChannelRef::Handle(h.to_string())));
+        let id = |i: &str| Some(YoutubeId::Channel(ChannelRef::Id(i.to_string())));
+
+        let cases = [
+            (
+                "https://www.youtube.com/@somechannel",
+                handle("somechannel"),
+            ),
+            (
+                "https://m.youtube.com/@some.channel/videos",
+                handle("some.channel"),
+            ),
+            ("youtube.com/@somechannel?si=abc", handle("somechannel")),
+            ("@somechannel", handle("somechannel")),
+            (
+                "https://www.youtube.com/channel/UCsBjURrPoezykLs9EqgamOA",
+                id("UCsBjURrPoezykLs9EqgamOA"),
+            ),
+            (
+                "https://www.youtube.com/channel/UCsBjURrPoezykLs9EqgamOA/featured",
+                id("UCsBjURrPoezykLs9EqgamOA"),
+            ),
+            ("UCsBjURrPoezykLs9EqgamOA", id("UCsBjURrPoezykLs9EqgamOA")),
+            (
+                "https://www.youtube.com/user/somename",
+                Some(YoutubeId::Channel(ChannelRef::Username(
+                    "somename".to_string(),
+                ))),
+            ),
+            ("https://www.youtube.com/@", None),
+            ("https://www.youtube.com/channel/", None),
+            ("@", None),
+            ("@some channel", None),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(get_youtube_id(input), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn test_published_range() {
+        let date = |s: &str| s.parse::<NaiveDate>().ok();
+        let range = PublishedRange {
+            since: date("2024-01-01"),
+            until: date("2024-06-30"),
+        };
+
+        assert!(range.contains(Some("2024-01-01T00:00:00Z")));
+        assert!(range.contains(Some("2024-06-30T23:59:59Z")));
+        assert!(!range.contains(Some("2023-12-31T23:59:59Z")));
+        assert!(!range.contains(Some("2024-07-01T00:00:00Z")));
+        assert!(!range.contains(None));
+        assert!(!range.contains(Some("yesterday")));
+        assert!(PublishedRange::default().contains(None));
+        assert!(
+            PublishedRange {
+                since: date("2024-01-01"),
+                until: None,
+            }
+            .contains(Some("2025-03-01T12:00:00+02:00"))
+        );
+    }
 }
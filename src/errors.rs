@@ -1,4 +1,6 @@
-use std::{fmt::Display, path::PathBuf};
+use std::{fmt::Display, ops::Range, path::PathBuf};
+
+use crate::core::duration::Duration;
 
 #[derive(Debug)]
 pub enum TConfigError {
@@ -7,6 +9,7 @@ pub enum TConfigError {
     ParseFailed(PathBuf),
     SerializingFailed(String),
     SaveFailed(String),
+    InvalidValue(String),
 }
 
 impl Display for TConfigError {
@@ -17,45 +20,241 @@ impl Display for TConfigError {
             TConfigError::ParseFailed(p) => write!(f, "could not parse file at path: {p:?}"),
             TConfigError::SerializingFailed(e) => write!(f, "could not serialize config: {e}"),
             TConfigError::SaveFailed(p) => write!(f, "could not save file to path: {p:?}"),
+            TConfigError::InvalidValue(e) => write!(f, "invalid config value: {e}"),
         }
     }
 }
 
+/// Points at the part of some input that failed to parse, e.g. an unknown unit in a duration.
+///
+/// It is rendered below the error message as the input with a caret under the offending part, followed by
+/// what was expected there and, for common typos, a suggested fix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The whole input, as given by the user.
+    pub input: String,
+    /// The byte range of the offending part. An empty range points between two characters, e.g. at a
+    /// missing unit.
+    pub span: Range<usize>,
+    /// What would have been accepted at this position.
+    pub expected: Vec<String>,
+    /// A replacement for the spanned part that would likely fix the input.
+    pub suggestion: Option<String>,
+}
+
+impl Diagnostic {
+    #[must_use]
+    pub fn new(input: &str, span: Range<usize>) -> Self {
+        Self {
+            input: input.to_string(),
+            span,
+            expected: Vec::new(),
+            suggestion: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_expected<I, S>(self, expected: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        Self {
+            expected: expected.into_iter().map(|e| e.to_string()).collect(),
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn with_suggestion(self, suggestion: &str) -> Self {
+        Self {
+            suggestion: Some(suggestion.to_string()),
+            ..self
+        }
+    }
+
+    /// The offending part of the input.
+    #[must_use]
+    pub fn snippet(&self) -> &str {
+        self.input.get(self.span.clone()).unwrap_or_default()
+    }
+
+    /// The input with the suggestion applied, if there is one.
+    #[must_use]
+    pub fn suggested_input(&self) -> Option<String> {
+        let suggestion = self.suggestion.as_ref()?;
+
+        Some(format!(
+            "{}{suggestion}{}",
+            self.input.get(..self.span.start)?,
+            self.input.get(self.span.end..)?
+        ))
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let column = self
+            .input
+            .get(..self.span.start)
+            .map_or(0, |s| s.chars().count());
+        let width = self.snippet().chars().count().max(1);
+
+        write!(
+            f,
+            "\n  {}\n  {}{}",
+            self.input,
+            " ".repeat(column),
+            "^".repeat(width)
+        )?;
+
+        if let Some((last, rest)) = self.expected.split_last() {
+            if rest.is_empty() {
+                write!(f, " expected {last}")?;
+            } else {
+                write!(f, " expected {} or {last}", rest.join(", "))?;
+            }
+        }
+
+        if let Some(input) = self.suggested_input() {
+            write!(f, "\n  help: did you mean `{input}`?")?;
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub enum TTimeError {
-    InvalidDurationFormat,
-    InvalidTimeUnit,
-    NegativeDuration,
-    InvalidMultiplierFormat,
-    MultiplierOutOfRange,
+    InvalidDurationFormat(Diagnostic),
+    InvalidTimeUnit(Diagnostic),
+    InvalidNumber(Diagnostic),
+    InvalidExpression(Diagnostic),
+    NegativeDuration(Diagnostic),
+    DurationOverflow,
+    InvalidMultiplierFormat(Diagnostic),
+    MultiplierOutOfRange((f64, f64)),
+    UnexpectedMultiplier,
+    InvalidTimeOfDay(String),
+    InvalidTimezone(String),
+    InvalidWeekdayBudget(String),
+}
+
+impl TTimeError {
+    /// The position in the input this error refers to, if any.
+    #[must_use]
+    pub fn diagnostic(&self) -> Option<&Diagnostic> {
+        match self {
+            Self::InvalidDurationFormat(d)
+            | Self::InvalidTimeUnit(d)
+            | Self::InvalidNumber(d)
+            | Self::InvalidExpression(d)
+            | Self::NegativeDuration(d)
+            | Self::InvalidMultiplierFormat(d) => Some(d),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn diagnostic_mut(&mut self) -> Option<&mut Diagnostic> {
+        match self {
+            Self::InvalidDurationFormat(d)
+            | Self::InvalidTimeUnit(d)
+            | Self::InvalidNumber(d)
+            | Self::InvalidExpression(d)
+            | Self::NegativeDuration(d)
+            | Self::InvalidMultiplierFormat(d) => Some(d),
+            _ => None,
+        }
+    }
 }
 
 impl Display for TTimeError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::InvalidTimeUnit => write!(
+            Self::InvalidTimeUnit(d) => write!(
+                f,
+                "unknown time unit `{}`; specify duration in seconds (s), minutes (m), hours (h), or days (d).{d}",
+                d.snippet()
+            ),
+            Self::InvalidDurationFormat(d) => write!(f, "invalid duration format.{d}"),
+            Self::InvalidNumber(d) => write!(f, "invalid number `{}`.{d}", d.snippet()),
+            Self::InvalidExpression(d) => write!(
                 f,
-                "specify duration in seconds (s), minutes (m), hours (h), or days (d)"
+                "invalid duration expression (check operators, operand types and parentheses).{d}"
             ),
-            Self::InvalidDurationFormat => write!(f, "invalid duration format!"),
-            Self::NegativeDuration => write!(f, "duration must be a positive value."),
-            Self::InvalidMultiplierFormat => {
-                write!(f, "multiplier must be a positive float.")
+            Self::NegativeDuration(d) => write!(f, "duration must be a positive value.{d}"),
+            Self::DurationOverflow => write!(f, "duration is too large to be represented."),
+            Self::InvalidMultiplierFormat(d) => {
+                write!(f, "multiplier must be a positive float.{d}")
+            }
+            Self::MultiplierOutOfRange((min, max)) => {
+                write!(f, "multiplier must be between {min}x and {max}x.")
+            }
+            Self::UnexpectedMultiplier => {
+                write!(f, "per-segment multipliers (`@`) are not allowed here.")
+            }
+            Self::InvalidTimeOfDay(t) => {
+                write!(f, "invalid time of day: {t} (expected HH:MM)")
             }
-            Self::MultiplierOutOfRange => {
-                write!(f, "multiplier must be greater than 1x and less than 100x.")
+            Self::InvalidTimezone(tz) => {
+                write!(
+                    f,
+                    "unknown timezone: {tz} (expected an IANA name, e.g. Europe/Berlin)"
+                )
+            }
+            Self::InvalidWeekdayBudget(spec) => {
+                write!(f, "invalid weekday budget: {spec} (expected e.g. sat=5h)")
             }
         }
     }
 }
 
+impl std::error::Error for TTimeError {}
+
+#[derive(Debug)]
+pub enum TCacheError {
+    NoCacheDir,
+    ReadFailed(String),
+    WriteFailed(String),
+}
+
+impl Display for TCacheError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TCacheError::NoCacheDir => write!(f, "could not determine the cache directory."),
+            TCacheError::ReadFailed(e) => write!(
+                f,
+                "could not read the cache file: {e} (run `ts cache clear` to start over)"
+            ),
+            TCacheError::WriteFailed(e) => write!(f, "could not write the cache file: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for TCacheError {}
+
 #[derive(Debug)]
 pub enum TYoutubeError {
     Reqwest,
     ResponseBodyParseFailure,
     ItemNotFound,
     InvalidPlaylist(String),
-    InvalidMaxSize((usize, usize)),
+    ChannelNotFound(String),
+    InvalidRange(String),
+    NotCached(String),
+    /// An error response from the API, with its HTTP status and message.
+    Api((u16, Option<String>)),
+    /// An error response meaning the API can't answer right now (a server error, an exhausted quota or a
+    /// rate limit), with its HTTP status and message.
+    Unavailable((u16, Option<String>)),
+}
+
+impl TYoutubeError {
+    /// Whether the request failed regardless of what was asked for, so that cached data can stand in.
+    #[must_use]
+    pub fn is_unavailable(&self) -> bool {
+        matches!(self, TYoutubeError::Reqwest | TYoutubeError::Unavailable(_))
+    }
 }
 
 impl Display for TYoutubeError {
@@ -73,13 +272,91 @@ impl Display for TYoutubeError {
             TYoutubeError::InvalidPlaylist(id) => {
                 write!(f, "invalid playlist: {id}")
             }
-            TYoutubeError::InvalidMaxSize((given, max)) => {
+            TYoutubeError::ChannelNotFound(channel) => {
+                write!(f, "channel not found: {channel}")
+            }
+            TYoutubeError::InvalidRange(range) => {
                 write!(
                     f,
-                    "max items ({given}) is larger than the length of the playlist ({max})."
+                    "invalid item range: {range} (expected positions from 1, e.g. 37..60, 37.. or ..60)"
                 )
             }
             TYoutubeError::ResponseBodyParseFailure => write!(f, "failed to parse response body."),
+            TYoutubeError::NotCached(what) => {
+                write!(f, "{what} is not cached, so it can't be looked up offline.")
+            }
+            TYoutubeError::Api((status, Some(message)))
+            | TYoutubeError::Unavailable((status, Some(message))) => {
+                write!(f, "the YouTube API responded with HTTP {status}: {message}")
+            }
+            TYoutubeError::Api((status, None)) | TYoutubeError::Unavailable((status, None)) => {
+                write!(f, "the YouTube API responded with HTTP {status}.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TYoutubeError {}
+
+/// Something worth pointing out about an otherwise successful request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TYoutubeWarning {
+    /// The requested items reached past the end of the playlist: `(first position, playlist length)`.
+    RangeClamped((usize, usize)),
+    /// The cache could not be opened, so nothing is cached.
+    CacheUnavailable(String),
+    /// The responses could not be written to the cache.
+    CacheNotSaved(String),
+    /// A request failed, so the rest is answered from the cache: `None` if YouTube couldn't be reached,
+    /// otherwise the HTTP status it responded with.
+    RequestFailed(Option<u16>),
+    /// The given number of videos were left out, since they couldn't be fetched and aren't cached.
+    NotCached(usize),
+    /// The results come from the cache, with the age of the oldest entry used.
+    Offline(Option<Duration>),
+}
+
+impl Display for TYoutubeWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TYoutubeWarning::RangeClamped((first, len)) if first > len => {
+                write!(
+                    f,
+                    "the playlist only has {len} items, so there is nothing to count from item {first}."
+                )
+            }
+            TYoutubeWarning::RangeClamped((first, len)) => {
+                write!(
+                    f,
+                    "the playlist only has {len} items; counting items {first} to {len}."
+                )
+            }
+            TYoutubeWarning::CacheUnavailable(e) => {
+                write!(
+                    f,
+                    "the cache could not be opened, so responses are not cached ({e})."
+                )
+            }
+            TYoutubeWarning::CacheNotSaved(e) => write!(f, "responses were not cached ({e})."),
+            TYoutubeWarning::RequestFailed(None) => {
+                write!(f, "could not reach YouTube; falling back to cached data.")
+            }
+            TYoutubeWarning::RequestFailed(Some(status)) => {
+                write!(
+                    f,
+                    "YouTube responded with HTTP {status}; falling back to cached data."
+                )
+            }
+            TYoutubeWarning::NotCached(count) => {
+                write!(f, "{count} video(s) are not cached and were left out.")
+            }
+            TYoutubeWarning::Offline(Some(age)) => write!(
+                f,
+                "offline; these results are based on cached data up to {age} old and may be out of date."
+            ),
+            TYoutubeWarning::Offline(None) => {
+                write!(f, "offline; these results are based on cached data.")
+            }
         }
     }
 }
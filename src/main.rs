@@ -1,10 +1,31 @@
 use clap::Parser;
-use trimsec::cli::Args;
+use trimsec::{
+    cli::{Args, flags::Flags},
+    core::format::TimeFormat,
+};
 
 fn main() {
     let args = Args::parse();
 
-    if let Err(err) = args.command.run(args.clip, args.color) {
+    let flags = Flags {
+        clip: args.clip,
+        day_ends: args.day_ends,
+        tz: args.tz,
+        start_at: args.start_at,
+        no_breaks: args.no_breaks,
+        from_start: args.from_start,
+        no_cache: args.no_cache,
+        refresh: args.refresh,
+        offline: args.offline,
+        format: TimeFormat {
+            unit: args.unit,
+            rounding: args.round,
+            subseconds: args.subseconds,
+            long: args.long,
+        },
+    };
+
+    if let Err(err) = args.command.run(&flags, args.color) {
         eprintln!("{err}");
         std::process::exit(1);
     }
@@ -0,0 +1,527 @@
+//! Runs the CLI against a local mock of the YouTube Data API, pointed to through `TRIMSEC_API_BASE`.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    ops::Deref,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+type Query = HashMap<String, String>;
+
+/// A request received by the mock, as its endpoint (e.g. `videos`) and query parameters.
+#[derive(Debug, Clone)]
+struct Request {
+    endpoint: String,
+    query: Query,
+}
+
+/// An HTTP server answering every request with the given handler, on a free local port.
+struct MockApi {
+    base: String,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl MockApi {
+    fn start(handler: impl Fn(&Request) -> (u16, String) + Send + 'static) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}/youtube/v3", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let received = Arc::clone(&requests);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                serve(stream, &handler, &received);
+            }
+        });
+
+        Self { base, requests }
+    }
+
+    fn requests(&self, endpoint: &str) -> Vec<Request> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.endpoint == endpoint)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Answers a single request, recording it before responding so that it's visible once the client is done.
+fn serve(
+    stream: TcpStream,
+    handler: &impl Fn(&Request) -> (u16, String),
+    received: &Mutex<Vec<Request>>,
+) -> Option<()> {
+    let mut reader = BufReader::new(stream.try_clone().ok()?);
+
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let target = line.split_whitespace().nth(1)?.to_string();
+
+    // skip the headers, as the client only sends GET requests without a body
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).ok()? == 0 || line.trim().is_empty() {
+            break;
+        }
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    let request = Request {
+        endpoint: path.rsplit('/').next()?.to_string(),
+        query: query
+            .split('&')
+            .filter_map(|p| p.split_once('='))
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+    };
+
+    let (status, body) = handler(&request);
+    received.lock().unwrap().push(request);
+
+    let response = format!(
+        "HTTP/1.1 {status} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    (&stream).write_all(response.as_bytes()).ok()
+}
+
+/// A scratch home directory for a test, holding the config and cache files, which is removed once dropped.
+struct Home(PathBuf);
+
+impl Deref for Home {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for Home {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn home(name: &str) -> Home {
+    let home = std::env::temp_dir().join(format!("trimsec-test-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&home);
+    std::fs::create_dir_all(&home).unwrap();
+    Home(home)
+}
+
+fn ts(home: &Path, base: &str, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ts"))
+        .args(args)
+        .env("HOME", home)
+        .env("XDG_CACHE_HOME", home.join(".cache"))
+        .env("XDG_CONFIG_HOME", home.join(".config"))
+        .env("TRIMSEC_YOUTUBE_KEY", "test-key")
+        .env("TRIMSEC_API_BASE", base)
+        .env("NO_PROXY", "127.0.0.1")
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+fn video_id(i: usize) -> String {
+    format!("vid{i:08}")
+}
+
+fn playlist(item_count: usize) -> String {
+    format!(r#"{{"items": [{{"contentDetails": {{"itemCount": {item_count}}}}}]}}"#)
+}
+
+fn playlist_items(ids: &[String], next_page_token: Option<&str>) -> String {
+    let items: Vec<String> = ids
+        .iter()
+        .map(|id| format!(r#"{{"contentDetails": {{"videoId": "{id}"}}}}"#))
+        .collect();
+    let token = next_page_token.map_or(String::new(), |t| format!(r#", "nextPageToken": "{t}""#));
+
+    format!(r#"{{"items": [{}]{token}}}"#, items.join(","))
+}
+
+/// Videos of a minute each, leaving out those that `exists` rejects.
+fn videos(query: &Query, exists: impl Fn(&str) -> bool) -> String {
+    let items: Vec<String> = query["id"]
+        .split(',')
+        .filter(|id| exists(id))
+        .map(|id| {
+            format!(
+                r#"{{"id": "{id}", "snippet": {{"title": "Video {id}", "description": ""}}, "contentDetails": {{"duration": "PT1M"}}}}"#
+            )
+        })
+        .collect();
+
+    format!(r#"{{"items": [{}]}}"#, items.join(","))
+}
+
+/// Serves a playlist of `item_count` one-minute videos, paged by `pageToken`s holding the next position.
+fn paged_playlist(item_count: usize) -> impl Fn(&Request) -> (u16, String) {
+    move |request| match request.endpoint.as_str() {
+        "playlists" => (200, playlist(item_count)),
+        "playlistItems" => {
+            let start: usize = request
+                .query
+                .get("pageToken")
+                .map_or(0, |t| t.parse().unwrap());
+            let max: usize = request.query["maxResults"].parse().unwrap();
+            let end = (start + max).min(item_count);
+
+            let ids: Vec<String> = (start..end).map(video_id).collect();
+            let next = (end < item_count).then(|| end.to_string());
+            (200, playlist_items(&ids, next.as_deref()))
+        }
+        "videos" => (200, videos(&request.query, |_| true)),
+        _ => (404, String::new()),
+    }
+}
+
+const PLAYLIST: &str = "https://www.youtube.com/playlist?list=PLmock";
+
+#[test]
+fn test_pagination() {
+    let api = MockApi::start(paged_playlist(120));
+    let home = home("pagination");
+
+    let output = ts(
+        &home,
+        &api.base,
+        &["yt", PLAYLIST, "-m", "2x", "--no-cache"],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Finishes in: 1h"));
+    assert!(stdout(&output).contains("Trimmed for 120 item(s)."));
+
+    let pages = api.requests("playlistItems");
+    assert_eq!(pages.len(), 3);
+    assert_eq!(pages[0].query.get("pageToken"), None);
+    assert_eq!(pages[2].query["pageToken"], "100");
+    assert_eq!(pages[2].query["maxResults"], "20");
+    assert!(pages.iter().all(|p| p.query["key"] == "test-key"));
+
+    // videos are fetched 50 at a time
+    let chunks: Vec<usize> = api
+        .requests("videos")
+        .iter()
+        .map(|r| r.query["id"].split(',').count())
+        .collect();
+    assert_eq!(chunks, vec![50, 50, 20]);
+}
+
+#[test]
+fn test_range_stops_paging() {
+    let api = MockApi::start(paged_playlist(120));
+    let home = home("range");
+
+    let output = ts(
+        &home,
+        &api.base,
+        &[
+            "yt",
+            PLAYLIST,
+            "-m",
+            "1x",
+            "--range",
+            "51..60",
+            "--no-cache",
+        ],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Trimmed for 10 item(s)."));
+
+    // everything before the range is paged through, but nothing after it
+    assert_eq!(api.requests("playlistItems").len(), 2);
+    assert_eq!(api.requests("videos")[0].query["id"].split(',').count(), 10);
+
+    // a range starting past the end takes nothing, so no pages are requested
+    let output = ts(
+        &home,
+        &api.base,
+        &["yt", PLAYLIST, "-m", "1x", "--range", "200..", "--no-cache"],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("the playlist only has 120 items"));
+    assert_eq!(api.requests("playlistItems").len(), 2);
+}
+
+#[test]
+fn test_duplicate_page_tokens() {
+    let api = MockApi::start(|request| match request.endpoint.as_str() {
+        "playlists" => (200, playlist(150)),
+        "playlistItems" => {
+            let start = if request.query.contains_key("pageToken") {
+                50
+            } else {
+                0
+            };
+            let ids: Vec<String> = (start..start + 50).map(video_id).collect();
+            (200, playlist_items(&ids, Some("again")))
+        }
+        "videos" => (200, videos(&request.query, |_| true)),
+        _ => (404, String::new()),
+    });
+    let home = home("tokens");
+
+    let output = ts(
+        &home,
+        &api.base,
+        &["yt", PLAYLIST, "-m", "1x", "--no-cache"],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Trimmed for 100 item(s)."));
+    assert_eq!(api.requests("playlistItems").len(), 2);
+}
+
+#[test]
+fn test_missing_items() {
+    // e.g. deleted or private videos, which stay in the playlist but aren't returned
+    let api = MockApi::start(|request| match request.endpoint.as_str() {
+        "videos" => (
+            200,
+            videos(&request.query, |id| id != video_id(1) && id != video_id(3)),
+        ),
+        _ => paged_playlist(5)(request),
+    });
+    let home = home("missing");
+
+    let output = ts(
+        &home,
+        &api.base,
+        &["yt", PLAYLIST, "-m", "1x", "--no-cache"],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Trimmed for 3 item(s)."));
+
+    let output = ts(
+        &home,
+        &api.base,
+        &["speed", PLAYLIST, "-b", "1h", "--no-cache"],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("counted 3 item(s)"));
+
+    let output = ts(&home, &api.base, &["list", PLAYLIST, "--no-cache"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains(&format!("Video {}", video_id(0))));
+    assert!(!stdout(&output).contains(&format!("Video {}", video_id(1))));
+}
+
+#[test]
+fn test_channel() {
+    let api = MockApi::start(|request| match request.endpoint.as_str() {
+        "channels" if request.query.get("forHandle").map(String::as_str) == Some("mock") => (
+            200,
+            r#"{"items": [{"contentDetails": {"relatedPlaylists": {"uploads": "UUmock"}}}]}"#
+                .to_string(),
+        ),
+        "channels" => (200, r#"{"items": []}"#.to_string()),
+        "playlists" if request.query["id"] == "UUmock" => (200, playlist(3)),
+        _ => paged_playlist(3)(request),
+    });
+    let home = home("channel");
+
+    let output = ts(&home, &api.base, &["yt", "@mock", "-m", "1x", "--no-cache"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Trimmed for 3 item(s)."));
+
+    let output = ts(
+        &home,
+        &api.base,
+        &["yt", "@nobody", "-m", "1x", "--no-cache"],
+    );
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("channel not found: @nobody"));
+}
+
+#[test]
+fn test_api_errors() {
+    let api = MockApi::start(|_| {
+        (
+            403,
+            r#"{"error": {"code": 403, "message": "The request cannot be completed because you have exceeded your quota."}}"#
+                .to_string(),
+        )
+    });
+    let home = home("errors");
+
+    let output = ts(&home, &api.base, &["yt", PLAYLIST, "-m", "1x"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("HTTP 403: The request cannot be completed"));
+
+    let api = MockApi::start(|_| (500, "not json".to_string()));
+    let output = ts(&home, &api.base, &["yt", PLAYLIST, "-m", "1x"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("HTTP 500."));
+
+    let api = MockApi::start(|_| (200, "not json".to_string()));
+    let output = ts(&home, &api.base, &["yt", PLAYLIST, "-m", "1x"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("failed to parse response body"));
+}
+
+/// A base URL whose server drops every connection without responding. The port stays bound, so that
+/// nothing else can take it over during the test.
+fn unreachable_base() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            drop(stream);
+        }
+    });
+    base
+}
+
+#[test]
+fn test_cache_and_offline_fallback() {
+    let api = MockApi::start(paged_playlist(60));
+    let home = home("cache");
+
+    let output = ts(&home, &api.base, &["yt", PLAYLIST, "-m", "1x"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let requests = api.requests.lock().unwrap().len();
+
+    // answered from the cache, without any further requests
+    let output = ts(&home, &api.base, &["yt", PLAYLIST, "-m", "1x"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Trimmed for 60 item(s)."));
+    assert_eq!(api.requests.lock().unwrap().len(), requests);
+
+    // refreshing can't reach the API, so it falls back to the cache
+    let base = unreachable_base();
+    let output = ts(&home, &base, &["yt", PLAYLIST, "-m", "1x", "--refresh"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Trimmed for 60 item(s)."));
+    assert!(stderr(&output).contains("could not reach YouTube"));
+
+    // without a cache, there's nothing to fall back to
+    let output = ts(&home, &base, &["yt", PLAYLIST, "-m", "1x", "--no-cache"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("error performing request"));
+}
+
+#[test]
+fn test_unavailable_fallback() {
+    let api = MockApi::start(paged_playlist(60));
+    let home = home("unavailable");
+
+    let output = ts(&home, &api.base, &["yt", PLAYLIST, "-m", "1x"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    // server errors and exhausted quotas fall back to the cache too
+    let api = MockApi::start(|_| (503, "not json".to_string()));
+    let output = ts(&home, &api.base, &["yt", PLAYLIST, "-m", "1x", "--refresh"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Trimmed for 60 item(s)."));
+    assert!(stderr(&output).contains("HTTP 503; falling back to cached data"));
+
+    let quota = MockApi::start(|_| {
+        (
+            403,
+            r#"{"error": {"code": 403, "message": "Quota exceeded.", "errors": [{"reason": "quotaExceeded"}]}}"#
+                .to_string(),
+        )
+    });
+    let output = ts(
+        &home,
+        &quota.base,
+        &["yt", PLAYLIST, "-m", "1x", "--refresh"],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Trimmed for 60 item(s)."));
+    assert!(stderr(&output).contains("HTTP 403; falling back to cached data"));
+
+    // the error stands for what isn't cached
+    let output = ts(
+        &home,
+        &quota.base,
+        &[
+            "yt",
+            "https://www.youtube.com/playlist?list=PLother",
+            "-m",
+            "1x",
+        ],
+    );
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("HTTP 403: Quota exceeded."));
+}
+
+#[test]
+fn test_plan_checks_inputs_first() {
+    let api = MockApi::start(paged_playlist(3));
+    let home = home("plan");
+
+    let output = ts(
+        &home,
+        &api.base,
+        &[
+            "plan",
+            PLAYLIST,
+            "-m",
+            "2x",
+            "--deadline",
+            "2000-01-01",
+            "--daily",
+            "1h",
+        ],
+    );
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("already in the past"));
+
+    let output = ts(
+        &home,
+        &api.base,
+        &[
+            "plan",
+            PLAYLIST,
+            "-m",
+            "fast",
+            "--deadline",
+            "2999-01-01",
+            "--daily",
+            "1h",
+        ],
+    );
+    assert!(!output.status.success());
+
+    // no quota is spent on invalid input
+    assert!(api.requests.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_offline_unlisted_items() {
+    // the count includes two videos that paging never returns
+    let api = MockApi::start(|request| match request.endpoint.as_str() {
+        "playlists" => (200, playlist(5)),
+        _ => paged_playlist(3)(request),
+    });
+    let home = home("unlisted");
+
+    let output = ts(&home, &api.base, &["yt", PLAYLIST, "-m", "1x"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Trimmed for 3 item(s)."));
+
+    let output = ts(&home, &api.base, &["yt", PLAYLIST, "-m", "1x", "--offline"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Trimmed for 3 item(s)."));
+}